@@ -5,6 +5,13 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use lazy_static::*;
 use slice_deque::SliceDeque;
 
+// The app is a binary crate so we can't import from it, but the ring buffer doesn't depend on anything else in the crate, so we can include the real one directly
+// The bench only uses some of its methods
+#[allow(dead_code)]
+#[path = "../src/helper/ring_buffer.rs"]
+mod ring_buffer;
+use ring_buffer::RingBuffer;
+
 lazy_static! {
     static ref FRAME_TIMES_VEC: Mutex<FrameTimesVec> = Mutex::new(FrameTimesVec {
         deltas: Vec::new(),
@@ -15,10 +22,21 @@ lazy_static! {
             deltas: SliceDeque::new(),
             fps: SliceDeque::new()
         });
+    static ref FRAME_TIMES_RING_BUFFER: Mutex<FrameTimesRingBuffer> =
+        Mutex::new(FrameTimesRingBuffer {
+            deltas: RingBuffer::new(NUM_RING_BUFFER_FRAME_TIMES_TO_TRACK),
+            fps: RingBuffer::new(NUM_RING_BUFFER_FRAME_TIMES_TO_TRACK),
+            scratch_deltas: Vec::new(),
+            scratch_fps: Vec::new()
+        });
 }
 // For NUM=120, Vec wins (22ns vs 1us)
 // For Num=12000, SliceDeque just wins (1us vs 1.4us)
 static NUM_FRAME_TIMES_TO_TRACK: usize = 3600usize;
+/// The ring buffer is `O(1)` per push, so it's benched with far more frames than the others (which are `O(n)`) would manage
+static NUM_RING_BUFFER_FRAME_TIMES_TO_TRACK: usize = 64 * 1024;
+/// How many frames get copied out to be "plotted", same as the default `num_frames_to_display`
+static NUM_FRAME_TIMES_TO_DISPLAY: usize = 1920usize;
 
 #[derive(Debug, Clone)]
 struct FrameTimesVec {
@@ -26,6 +44,14 @@ struct FrameTimesVec {
     fps: Vec<f32>,
 }
 
+#[derive(Debug, Clone)]
+struct FrameTimesRingBuffer {
+    deltas: RingBuffer<f32>,
+    fps: RingBuffer<f32>,
+    scratch_deltas: Vec<f32>,
+    scratch_fps: Vec<f32>,
+}
+
 #[derive(Debug, Clone)]
 struct FrameTimesSliceDeque {
     deltas: SliceDeque<f32>,
@@ -75,11 +101,32 @@ fn bench_slice_deque(delta: f32) {
     plot_fake(f);
 }
 
+fn bench_ring_buffer(delta: f32) {
+    let mut guard_frame_times = match FRAME_TIMES_RING_BUFFER.lock() {
+        Err(poisoned) => poisoned.into_inner(),
+        Ok(guard) => guard,
+    };
+    let frame_times: &mut FrameTimesRingBuffer = guard_frame_times.deref_mut();
+
+    // Overwrites the oldest value once full, so no need to truncate
+    frame_times.deltas.push(delta);
+    frame_times.fps.push(1f32 / delta);
+
+    // Plotting needs a contiguous slice, so copy out the displayed frames like the UI does
+    frame_times.deltas.copy_recent_into(NUM_FRAME_TIMES_TO_DISPLAY, &mut frame_times.scratch_deltas);
+    frame_times.fps.copy_recent_into(NUM_FRAME_TIMES_TO_DISPLAY, &mut frame_times.scratch_fps);
+    plot_fake(&frame_times.scratch_deltas);
+    plot_fake(&frame_times.scratch_fps);
+}
+
 fn criterion_benchmark(criterion: &mut Criterion) {
     criterion.bench_function("vec", |b| b.iter(|| bench_vec(black_box(1f32 / 69f32))));
     criterion.bench_function("slice_deque", |b| {
         b.iter(|| bench_slice_deque(black_box(1f32 / 69f32)))
     });
+    criterion.bench_function("ring_buffer_64k", |b| {
+        b.iter(|| bench_ring_buffer(black_box(1f32 / 69f32)))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
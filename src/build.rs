@@ -1,3 +1,23 @@
 //! Build information, generated by [shadow-rs]
 include!(concat!(env!("OUT_DIR"), "/shadow.rs"));
+
+/// URL of the project's source code repository
+pub const REPOSITORY_URL: &str = env!("CARGO_PKG_REPOSITORY");
+
+/// The build metadata as `(label, value)` pairs, for showing to the user and attaching to error reports
+pub fn build_info() -> [(&'static str, &'static str); 6] {
+    [
+        ("Project", PROJECT_NAME),
+        ("Version", PKG_VERSION),
+        ("Target", BUILD_TARGET),
+        ("Commit", COMMIT_HASH),
+        ("Branch", BRANCH),
+        ("Built", BUILD_TIME),
+    ]
+}
+
+/// The [build_info] formatted as text, one `label: value` per line
+pub fn build_info_text() -> String {
+    build_info().iter().map(|(label, value)| format!("{label}: {value}")).collect::<Vec<_>>().join("\n")
+}
 // shadow!(build);
@@ -1,5 +1,6 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::time::Duration;
 
 lazy_static! {
     /// Regex that filters a file path to select only font files
@@ -14,3 +15,15 @@ lazy_static! {
     pub static ref FONTS_FILE_NAME_EXTRACTOR : Regex = Regex::new(r"[\\/](?P<base_font_name>[\w \-_\.]*) \((?P<weight_name>[\w \-_\.]*)\)\.(?P<file_extension>\w+)")
     .expect("compile-time regex constant should be valid");
 }
+
+/// Name of the font that is embedded into the binary (see [FALLBACK_FONT_DATA])
+pub const FALLBACK_FONT_NAME: &str = "JetBrains Mono (Embedded)";
+/// Name of the weight of the embedded font (see [FALLBACK_FONT_DATA])
+pub const FALLBACK_FONT_WEIGHT_NAME: &str = "Regular";
+/// Font data that is embedded into the binary, so that the UI still has a font when none could be loaded from the resources folder
+pub const FALLBACK_FONT_DATA: &[u8] = include_bytes!("../../resources/app_resources/fonts/jetbrains mono/JetBrains Mono (Regular).ttf");
+
+/// How often the resource folders are checked for changes, when hot-reloading is enabled (see [ResourcesConfig::hot_reload](crate::config::run_time::resources_config::ResourcesConfig::hot_reload))
+pub const RESOURCE_WATCH_INTERVAL: Duration = Duration::from_millis(500);
+/// How long the files in a resource folder have to stay unchanged before they're reloaded, so we don't reload halfway through a file being saved
+pub const RESOURCE_WATCH_DEBOUNCE: Duration = Duration::from_millis(1000);
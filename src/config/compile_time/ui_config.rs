@@ -1,8 +1,21 @@
+use std::time::Duration;
+
 /// The minimum size (in pixels) that can be used when selecting the size of a font
 pub const MIN_FONT_SIZE: f32 = 8f32;
 /// The maximum allowed size for a font (in pixels)
 pub const MAX_FONT_SIZE: f32 = 128f32;
 /// The maximum number of frames (see [crate::ui::ui_system::FrameInfo]) that should be tracked
 pub const MAX_FRAMES_TO_TRACK: usize = 64_000;
+/// The largest value that can be chosen for [max_displayed_errors](crate::config::run_time::ui_config::UiConfig::max_displayed_errors)
+pub const MAX_DISPLAYED_ERRORS: usize = 1_000;
+/// The maximum number of errors that will be kept in the error history. Older entries are removed first
+pub const MAX_ERROR_HISTORY: usize = 256;
+/// When [power_saving](crate::config::init_time::ui_config::UiConfig::power_saving) is enabled, the longest the UI will go without redrawing.
+/// The UI thread also only checks for messages when it wakes up, so this is how long it can take to respond to them
+pub const POWER_SAVING_MAX_IDLE: Duration = Duration::from_millis(100);
+/// How long a [Toast](crate::ui::ui_system::Toast) is shown for (e.g. the confirmation after saving a screenshot)
+pub const TOAST_DURATION: Duration = Duration::from_secs(3);
+/// How many times the config UI tries to write the user's changes back, if the config keeps being changed by something else at the same time
+pub const MAX_CONFIG_UPDATE_ATTEMPTS: usize = 5;
 
 //TODO: Get rid of these, make them constraints in the IMGUI code to display the config
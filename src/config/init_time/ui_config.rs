@@ -1,8 +1,10 @@
+use imgui_winit_support::HiDpiMode;
 use serde::{Deserialize, Serialize};
 use winit::dpi::{LogicalSize, Size};
 
 /// Base configuration struct that contains options that configure the entire app
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct UiConfig {
     /// Whether the main OS window should start maximised (when created initially)
     pub start_maximised: bool,
@@ -16,6 +18,68 @@ pub struct UiConfig {
     ///
     /// Must be a power of 2
     pub multisampling: u16,
+    /// Whether the OpenGL context should use an sRGB framebuffer
+    ///
+    /// see [glutin::ContextBuilder::with_srgb]
+    pub srgb: bool,
+    /// Which renderer draws the UI
+    pub backend: Backend,
+    /// How the UI should be scaled on high DPI displays
+    pub hidpi_mode: HiDpiSetting,
+    /// Index of the monitor the window should open on (in the order the OS lists them), or [None] for the OS's choice
+    pub start_monitor: Option<usize>,
+    /// Position (in physical pixels) of the top-left corner of the window when it opens.
+    /// Relative to the [start_monitor](UiConfig::start_monitor) if there is one, otherwise relative to the whole desktop
+    pub start_position: Option<[i32; 2]>,
+    /// Size (in physical pixels) of the inside of the window when it opens (if it isn't maximised), or [None] for the OS's choice
+    ///
+    /// This and the position are updated to the window's current geometry when the app is closed, so they're restored next launch
+    pub window_size: Option<[u32; 2]>,
+    /// Maximum number of frames per second to draw, or [None] to draw as fast as possible (or as fast as [vsync](UiConfig::vsync) allows)
+    pub frame_limit: Option<f32>,
+    /// Only redraws when there's input (or every [POWER_SAVING_MAX_IDLE](crate::config::compile_time::ui_config::POWER_SAVING_MAX_IDLE)), instead of constantly
+    pub power_saving: bool,
+}
+
+/// The renderers that can be used to draw the UI
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Backend {
+    /// Renders using [glium](imgui_glium_renderer)
+    Glium,
+}
+
+impl Backend {
+    /// All the backends, in the order they should be displayed
+    pub const ALL: [Backend; 1] = [Backend::Glium];
+
+    /// Name of the backend to display to the user
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Glium => "Glium",
+        }
+    }
+}
+
+/// Config version of [HiDpiMode], so it can be (de)serialised
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HiDpiSetting {
+    /// Uses the scale factor the OS reports (see [HiDpiMode::Default])
+    Default,
+    /// Uses the scale factor the OS reports, rounded to the nearest integer (see [HiDpiMode::Rounded])
+    Rounded,
+    /// Always uses the given scale factor, ignoring the OS (see [HiDpiMode::Locked])
+    Locked(f64),
+}
+
+impl HiDpiSetting {
+    /// Converts to the equivalent [HiDpiMode] for [imgui_winit_support]
+    pub fn to_hidpi_mode(self) -> HiDpiMode {
+        match self {
+            HiDpiSetting::Default => HiDpiMode::Default,
+            HiDpiSetting::Rounded => HiDpiMode::Rounded,
+            HiDpiSetting::Locked(scale_factor) => HiDpiMode::Locked(scale_factor),
+        }
+    }
 }
 
 impl std::default::Default for UiConfig {
@@ -25,6 +89,14 @@ impl std::default::Default for UiConfig {
             vsync: false,
             hardware_acceleration: Some(true),
             multisampling: 2,
+            srgb: true,
+            backend: Backend::Glium,
+            hidpi_mode: HiDpiSetting::Default,
+            start_monitor: None,
+            start_position: None,
+            window_size: None,
+            frame_limit: None,
+            power_saving: false,
         }
     }
 }
@@ -1,6 +1,9 @@
 use crate::config::compile_time::config_config::*;
 use std::fs;
 use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
 use std::sync::Mutex;
 
 /// # Config
@@ -32,36 +35,71 @@ pub struct AppConfig {
     pub runtime: RuntimeAppConfig,
 }
 
+/// Set when the config file exists, but couldn't be loaded when the app started (see [config_load_failed])
+static CONFIG_LOAD_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Gets the path of the config file, which is next to the executable
+pub fn config_file_path() -> Res<PathBuf> {
+    Ok(app_current_directory()?.join(BASE_CONFIG_PATH))
+}
+
 /// Attempts to save the currently loaded config to disk
 pub fn save_config_to_disk() -> FallibleFn {
-    let config_path = app_current_directory()?.join(BASE_CONFIG_PATH);
+    let config = save_config_to(&config_file_path()?)?;
+    *SAVED_CONFIG.lock() = config;
+    // The file has a valid config in it now, so it's fine to overwrite it again
+    CONFIG_LOAD_FAILED.store(false, Relaxed);
+
+    Ok(())
+}
+
+/// Saves the currently loaded config to the file at `path`, returning the config that was saved
+///
+/// Unlike [save_config_to_disk], this doesn't change what counts as [unsaved changes](has_unsaved_changes)
+pub fn save_config_to(path: &Path) -> Res<AppConfig> {
     let config = read_config_value(|config| config.clone());
 
     let serialised = to_string_pretty(&config, PrettyConfig::default().separate_tuple_members(true).enumerate_arrays(true)).wrap_err("couldn't serialise config")?;
 
-    fs::write(config_path, serialised).wrap_err("couldn't save serialised config to file")?;
+    fs::write(path, serialised)
+        .wrap_err("couldn't save serialised config to file")
+        .with_note(|| format!("path: {}", path.display()))?;
 
-    Ok(())
+    Ok(config)
 }
 
 /// Loads the config from disk, if possible
 pub fn load_config_from_disk() -> FallibleFn {
     let new_config = fallible_get_disk_config().wrap_err("could not load config from disk")?;
+    *SAVED_CONFIG.lock() = new_config.clone();
+    CONFIG_LOAD_FAILED.store(false, Relaxed);
     update_config(|config_ref| *config_ref = new_config);
     Ok(())
 }
 
+/// Whether the config file exists, but couldn't be loaded when the app started (so the default config is being used instead)
+///
+/// If so, the file shouldn't be overwritten without the user asking, otherwise their config would be replaced with the defaults
+pub fn config_load_failed() -> bool {
+    lazy_static::initialize(&SAVED_CONFIG);
+    CONFIG_LOAD_FAILED.load(Relaxed)
+}
+
 /// Internal function that tries to get the config from disk. Can fail (and if so returns the error instead)
 fn fallible_get_disk_config() -> Res<AppConfig> {
     //load up the file
-    let config_path = app_current_directory()?.join(BASE_CONFIG_PATH);
+    let config_path = config_file_path()?;
     let data = fs::read_to_string(&config_path).wrap_err_with(|| format!("could not read init config file at {config_path:?}"))?;
     let config = ron::from_str::<AppConfig>(&data).wrap_err("failed to deserialise config").section(data.header("Config Data"))?;
 
     Ok(config)
 }
 lazy_static! {
-    static ref CONFIG_INSTANCE: Mutex<AppConfig> = Mutex::new(
+    /// The config as it was last saved to (or loaded from) disk, so we can tell if there are [unsaved changes](has_unsaved_changes).
+    /// If the config couldn't be loaded at startup, this is the default config the app started with instead
+    ///
+    /// Uses a [parking_lot] mutex, since nothing can panic while holding it (unlike [CONFIG_INSTANCE]), so there's no need to deal with poisoning
+    static ref SAVED_CONFIG: parking_lot::Mutex<AppConfig> = parking_lot::Mutex::new(
     {
         // Again, we can't using [tracing] so we gotta use println (ew)
         let maybe_config = fallible_get_disk_config();
@@ -71,11 +109,34 @@ lazy_static! {
             let report =
                 report.wrap_err("using default font config (could not load config from file)");
             eprintln!("problem loading config: {:?}", report);
+            // A missing file is fine (e.g. the first time the app runs), but if it's there then it's the user's config and we couldn't read it
+            if config_file_path().map_or(false, |path| path.exists()) {
+                CONFIG_LOAD_FAILED.store(true, Relaxed);
+            }
             AppConfig::default()
             }
         }
     }
     );
+    static ref CONFIG_INSTANCE: Mutex<AppConfig> = Mutex::new(SAVED_CONFIG.lock().clone());
+}
+
+/// Whether the config has been changed since it was last saved to (or loaded from) disk
+///
+/// Things the app keeps track of by itself (which windows are open, and where the window is) are ignored, since they change all the time and aren't something the user would think of as a change to the config
+pub fn has_unsaved_changes() -> bool {
+    let saved = SAVED_CONFIG.lock().clone();
+    let mut current = read_config_value(|config| config.clone());
+
+    // Copy the app-managed values over from the saved config, so that differences in them don't count
+    current.runtime.ui.open_windows = saved.runtime.ui.open_windows.clone();
+    let (ui_config, saved_ui_config) = (&mut current.init.ui_config, &saved.init.ui_config);
+    ui_config.start_maximised = saved_ui_config.start_maximised;
+    ui_config.start_monitor = saved_ui_config.start_monitor;
+    ui_config.start_position = saved_ui_config.start_position;
+    ui_config.window_size = saved_ui_config.window_size;
+
+    current != saved
 }
 
 /// Reads a config value from the global [AppConfig], and returns it
@@ -120,3 +181,123 @@ pub fn update_config<T, F: FnOnce(&mut AppConfig) -> T>(func: F) -> T {
     drop(guard);
     result
 }
+
+/// Updates the config, but only if it hasn't been changed from the `expected` config
+///
+/// This is a compare-and-swap: if the current config is equal to `expected`, `func` is called to modify it, and `true` is returned.
+/// Otherwise the config was changed by someone else in the meantime, so `func` is not called and `false` is returned. The caller should re-read the config and try again
+///
+/// Use this instead of [update_config] when the new config was calculated from an earlier read of the config, so that changes made in between aren't overwritten
+pub fn try_update_config<F: FnOnce(&mut AppConfig)>(expected: &AppConfig, func: F) -> bool {
+    update_config(|config| {
+        if config != expected {
+            return false;
+        }
+        func(config);
+        true
+    })
+}
+
+/// Applies the changes that were made between `original` and `modified` on top of `fresh`, for when [try_update_config] fails
+///
+/// Anything that wasn't changed keeps its value from `fresh`, so changes made by something else in the meantime aren't lost.
+/// Works field-by-field (down to the individual values), so only a field that was changed in both places takes the value from `modified`
+pub fn reapply_changes(fresh: &AppConfig, original: &AppConfig, modified: &AppConfig) -> Res<AppConfig> {
+    let to_value = |config: &AppConfig| serde_json::to_value(config).wrap_err("couldn't convert config to a value to merge");
+    let merged = merge_changes(to_value(fresh)?, &to_value(original)?, &to_value(modified)?);
+    serde_json::from_value(merged).wrap_err("couldn't convert merged value back into a config")
+}
+
+/// Recursive part of [reapply_changes]
+fn merge_changes(fresh: serde_json::Value, original: &serde_json::Value, modified: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value::Object;
+
+    if original == modified {
+        return fresh;
+    }
+    match (fresh, original, modified) {
+        (Object(mut fresh), Object(original), Object(modified)) => {
+            for (key, modified_value) in modified {
+                let merged = match (fresh.remove(key), original.get(key)) {
+                    (Some(fresh_value), Some(original_value)) => merge_changes(fresh_value, original_value, modified_value),
+                    // Added by the user (e.g. a new entry in a map), or removed in the meantime
+                    _ => modified_value.clone(),
+                };
+                fresh.insert(key.clone(), merged);
+            }
+            // Removed by the user
+            for key in original.keys().filter(|key| !modified.contains_key(*key)) {
+                fresh.remove(key);
+            }
+            Object(fresh)
+        }
+        // Values that aren't structs or maps (numbers, strings, lists, etc.) are replaced as a whole
+        (_, _, modified) => modified.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn config_with_missing_fields_loads() -> FallibleFn {
+        let serialised = to_string_pretty(&AppConfig::default(), PrettyConfig::default())?;
+        // Pretend this is a config saved before these fields were added
+        // Only single-line fields, so removing the line removes the whole field
+        let old_fields = ["auto_save_config_on_exit:", "font_size_step:", "max_displayed_errors:", "hot_reload:", "power_saving:"];
+        let old_config = serialised.lines().filter(|line| !old_fields.iter().any(|field| line.trim_start().starts_with(field))).join("\n");
+        assert_ne!(old_config, serialised);
+
+        let loaded: AppConfig = ron::from_str(&old_config)?;
+        assert_eq!(loaded, AppConfig::default());
+        Ok(())
+    }
+
+    #[test]
+    fn reapply_changes_keeps_other_changes() -> FallibleFn {
+        let original = AppConfig::default();
+        let mut modified = original.clone();
+        modified.runtime.ui.font_size += 1.0;
+        modified.runtime.ui.open_windows.insert("Test Window".to_string(), true);
+        // Changed at the same time, by something other than the config UI
+        let mut fresh = original.clone();
+        fresh.runtime.ui.font_size_step += 1.0;
+        fresh.runtime.auto_save_config_on_exit = !original.runtime.auto_save_config_on_exit;
+
+        let mut expected = fresh.clone();
+        expected.runtime.ui.font_size = modified.runtime.ui.font_size;
+        expected.runtime.ui.open_windows = modified.runtime.ui.open_windows.clone();
+        assert_eq!(reapply_changes(&fresh, &original, &modified)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn reapply_changes_prefers_the_users_change() -> FallibleFn {
+        let original = AppConfig::default();
+        let mut modified = original.clone();
+        modified.runtime.ui.font_size = 30.0;
+        let mut fresh = original.clone();
+        fresh.runtime.ui.font_size = 40.0;
+
+        assert_eq!(reapply_changes(&fresh, &original, &modified)?, modified);
+        Ok(())
+    }
+
+    #[test]
+    fn reapply_changes_removes_map_entries() -> FallibleFn {
+        let mut original = AppConfig::default();
+        original.runtime.ui.open_windows.insert("Removed".to_string(), true);
+        let mut modified = original.clone();
+        modified.runtime.ui.open_windows.remove("Removed");
+        let mut fresh = original.clone();
+        fresh.runtime.ui.open_windows.insert("Added Elsewhere".to_string(), false);
+
+        let mut expected = modified.clone();
+        expected.runtime.ui.open_windows.insert("Added Elsewhere".to_string(), false);
+        assert_eq!(reapply_changes(&fresh, &original, &modified)?, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// Config for the engine (the part that does the actual rendering)
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct EngineConfig {
+    /// Seed for everything random (see [new_rng()](crate::helper::rng::new_rng)), so that renders can be reproduced.
+    /// If [None], a different random seed is used every time
+    pub seed: Option<u64>,
+}
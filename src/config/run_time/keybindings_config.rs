@@ -7,6 +7,7 @@ pub type KeyCode = imgui_winit_support::winit::event::VirtualKeyCode;
 
 /// Config struct that holds keybinding values
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(default)]
 pub struct KeybindingsConfig {
     /// Toggles the visibility of the [imgui] metrics window (see [imgui::Ui::show_metrics_window()])
     pub toggle_metrics_window: KeyBinding,
@@ -16,6 +17,23 @@ pub struct KeybindingsConfig {
     pub toggle_ui_managers_window: KeyBinding,
 
     pub toggle_config_window: KeyBinding,
+    /// Toggles the visibility of the error history window
+    pub toggle_error_history_window: KeyBinding,
+    /// Toggles the visibility of the help window, which lists all the keybindings
+    pub toggle_help_window: KeyBinding,
+    /// Toggles explain mode, where hovering over a control shows a longer description of what it does
+    pub toggle_explain_mode: KeyBinding,
+
+    /// Makes the UI font bigger, by [font_size_step](crate::config::run_time::ui_config::UiConfig::font_size_step)
+    pub increase_font_size: KeyBinding,
+    /// Makes the UI font smaller, by [font_size_step](crate::config::run_time::ui_config::UiConfig::font_size_step)
+    pub decrease_font_size: KeyBinding,
+    /// Opens the command palette, for searching through and running any action
+    pub toggle_command_palette: KeyBinding,
+    /// Saves a screenshot of the whole window to the screenshots folder
+    pub save_screenshot: KeyBinding,
+    /// Runs the engine loop once, while the engine is paused
+    pub step_engine: KeyBinding,
 
     /// (kinda) Dummy keybinding for exiting the app
     ///
@@ -48,23 +66,18 @@ impl Display for KeyBinding {
 }
 
 impl KeyBinding {
-    /// Checks whether all the *required* modifiers are being held for the keybinding. Ignores modifiers that aren't required (e.g. if [Self::modifier_shift] == false)
-    pub fn required_modifiers_held(&self, ui: &imgui::Ui) -> bool {
-        /*
-        # Mini little truth table of what we want
-        |Want|Down| Output|
-        |Yes | Yes| Yes   |
-        |Yes | No | No    |
-        |No  | Yes| Yes   |
-        |No  | No | Yes   |
-         */
-        // true if we don't have the modifier, or either of the L/R shifts is held
-        let shift = !self.modifier_shift || (ui.is_key_index_down(KeyCode::LShift as i32) || ui.is_key_index_down(KeyCode::RShift as i32));
-        let ctrl = !self.modifier_ctrl || (ui.is_key_index_down(KeyCode::LControl as i32) || ui.is_key_index_down(KeyCode::RControl as i32));
-        let alt = !self.modifier_alt || (ui.is_key_index_down(KeyCode::LAlt as i32) || ui.is_key_index_down(KeyCode::RAlt as i32));
-
-        // If all modifiers are pressed (or not required), then we are happy
-        ctrl && shift && alt
+    /// Checks whether exactly the modifiers for the keybinding are being held
+    ///
+    /// Modifiers the keybinding doesn't use mustn't be held either, otherwise e.g. `Shift + F1` would also trigger a binding for plain `F1`
+    pub fn modifiers_held(&self, ui: &imgui::Ui) -> bool {
+        // Either of the left/right keys counts
+        let held = |left: KeyCode, right: KeyCode| ui.is_key_index_down(left as i32) || ui.is_key_index_down(right as i32);
+        self.modifiers_match(held(KeyCode::LControl, KeyCode::RControl), held(KeyCode::LAlt, KeyCode::RAlt), held(KeyCode::LShift, KeyCode::RShift))
+    }
+
+    /// Whether the held modifiers are exactly the ones this keybinding needs, see [Self::modifiers_held]
+    pub fn modifiers_match(&self, ctrl: bool, alt: bool, shift: bool) -> bool {
+        self.modifier_ctrl == ctrl && self.modifier_alt == alt && self.modifier_shift == shift
     }
 }
 
@@ -78,7 +91,7 @@ impl Default for KeybindingsConfig {
                 modifier_shift: false,
             },
             toggle_demo_window: KeyBinding {
-                shortcut: KeyCode::F1,
+                shortcut: KeyCode::F2,
                 modifier_ctrl: false,
                 modifier_alt: false,
                 modifier_shift: false,
@@ -101,6 +114,54 @@ impl Default for KeybindingsConfig {
                 modifier_alt: false,
                 modifier_shift: false,
             },
+            toggle_error_history_window: KeyBinding {
+                shortcut: KeyCode::F7,
+                modifier_ctrl: false,
+                modifier_alt: false,
+                modifier_shift: false,
+            },
+            toggle_help_window: KeyBinding {
+                shortcut: KeyCode::F1,
+                modifier_ctrl: false,
+                modifier_alt: false,
+                modifier_shift: true,
+            },
+            toggle_explain_mode: KeyBinding {
+                shortcut: KeyCode::F1,
+                modifier_ctrl: false,
+                modifier_alt: false,
+                modifier_shift: false,
+            },
+            increase_font_size: KeyBinding {
+                shortcut: KeyCode::Equals,
+                modifier_ctrl: true,
+                modifier_alt: false,
+                modifier_shift: false,
+            },
+            decrease_font_size: KeyBinding {
+                shortcut: KeyCode::Minus,
+                modifier_ctrl: true,
+                modifier_alt: false,
+                modifier_shift: false,
+            },
+            toggle_command_palette: KeyBinding {
+                shortcut: KeyCode::P,
+                modifier_ctrl: true,
+                modifier_alt: false,
+                modifier_shift: false,
+            },
+            save_screenshot: KeyBinding {
+                shortcut: KeyCode::F12,
+                modifier_ctrl: false,
+                modifier_alt: false,
+                modifier_shift: false,
+            },
+            step_engine: KeyBinding {
+                shortcut: KeyCode::F10,
+                modifier_ctrl: false,
+                modifier_alt: false,
+                modifier_shift: false,
+            },
         }
     }
 }
@@ -110,4 +171,84 @@ impl KeybindingsConfig {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Returns (a copy of) every keybinding, along with a human-readable name and description for it
+    pub fn bindings(&self) -> [(&'static str, &'static str, KeyBinding); 13] {
+        let mut copy = *self;
+        copy.bindings_mut().map(|(name, description, binding)| (name, description, *binding))
+    }
+
+    /// Returns the names of every keybinding that is bound to the same key and modifiers as `binding`
+    pub fn names_bound_to(&self, binding: &KeyBinding) -> Vec<&'static str> {
+        self.bindings().into_iter().filter(|(_, _, other)| other == binding).map(|(name, _, _)| name).collect()
+    }
+
+    /// Finds every pair of keybindings that are bound to the same key and modifiers (so pressing it would trigger both)
+    pub fn find_conflicts(&self) -> Vec<(&'static str, &'static str)> {
+        let bindings = self.bindings();
+        let mut conflicts = vec![];
+        for (index, (name, _, binding)) in bindings.iter().enumerate() {
+            for (other_name, _, other_binding) in &bindings[index + 1..] {
+                if binding == other_binding {
+                    conflicts.push((*name, *other_name));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Returns every keybinding, along with a human-readable name and description for it
+    ///
+    /// This is the one place the names and descriptions live, so anything listing the keybindings (like the help window) should go through here
+    pub fn bindings_mut(&mut self) -> [(&'static str, &'static str, &mut KeyBinding); 13] {
+        [
+            ("Toggle Metrics Window", "Shows/hides the ImGUI metrics window", &mut self.toggle_metrics_window),
+            ("Toggle Demo Window", "Shows/hides the ImGUI demo window", &mut self.toggle_demo_window),
+            (
+                "Toggle UI Managers Window",
+                "Shows/hides the UI management window (fonts, frame timings, etc)",
+                &mut self.toggle_ui_managers_window,
+            ),
+            ("Toggle Config Window", "Shows/hides the config window", &mut self.toggle_config_window),
+            (
+                "Toggle Error History Window",
+                "Shows/hides the list of every error that has occurred",
+                &mut self.toggle_error_history_window,
+            ),
+            ("Toggle Help Window", "Shows/hides the help window, which lists these keybindings", &mut self.toggle_help_window),
+            (
+                "Toggle Explain Mode",
+                "While on, hovering over a control shows a longer explanation of what it does",
+                &mut self.toggle_explain_mode,
+            ),
+            ("Increase Font Size", "Makes the UI font bigger", &mut self.increase_font_size),
+            ("Decrease Font Size", "Makes the UI font smaller", &mut self.decrease_font_size),
+            ("Toggle Command Palette", "Opens a searchable list of every action", &mut self.toggle_command_palette),
+            ("Save Screenshot", "Saves a screenshot of the window to the screenshots folder", &mut self.save_screenshot),
+            ("Step Engine", "Runs the engine loop once, while the engine is paused", &mut self.step_engine),
+            ("Exit App", "Exits the app, the same as closing the window", &mut self.exit_app),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn extra_modifiers_dont_match() {
+        let config = KeybindingsConfig::default();
+        // Help is Shift + F1, explain mode is plain F1
+        assert!(config.toggle_help_window.modifiers_match(false, false, true));
+        assert!(!config.toggle_explain_mode.modifiers_match(false, false, true));
+        assert!(config.toggle_explain_mode.modifiers_match(false, false, false));
+        assert!(!config.toggle_help_window.modifiers_match(false, false, false));
+        assert!(!config.toggle_help_window.modifiers_match(true, false, true));
+    }
+
+    #[test]
+    fn defaults_dont_conflict() {
+        assert_eq!(KeybindingsConfig::default().find_conflicts(), vec![]);
+    }
 }
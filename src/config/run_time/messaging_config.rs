@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// Config for how the threads communicate with each other
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MessagingConfig {
+    /// How long the program thread sleeps between checking for messages and making sure the other threads are still running, in milliseconds.
+    /// Lower values mean a crashed thread is noticed sooner, at the cost of waking up more often
+    pub thread_poll_interval_ms: u64,
+}
+
+impl Default for MessagingConfig {
+    fn default() -> Self {
+        Self { thread_poll_interval_ms: 50 }
+    }
+}
@@ -1,21 +1,44 @@
 //! This module defines the configuration struct(s) that configure options for the entire application
 
+pub mod engine_config;
 pub mod keybindings_config;
+pub mod messaging_config;
 pub mod resources_config;
 pub mod tracing_config;
 pub mod ui_config;
 
+use engine_config::EngineConfig;
 use keybindings_config::*;
+use messaging_config::MessagingConfig;
 use resources_config::ResourcesConfig;
 use serde::{Deserialize, Serialize};
 use tracing_config::TracingConfig;
 use ui_config::UiConfig;
 
 /// Base configuration struct that contains options that configure the entire app
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct RuntimeAppConfig {
+    pub engine: EngineConfig,
     pub keybindings: KeybindingsConfig,
+    pub messaging: MessagingConfig,
     pub resources: ResourcesConfig,
     pub tracing: TracingConfig,
     pub ui: UiConfig,
+    /// Whether the config is saved to disk when the app exits cleanly (e.g. the user closes the window)
+    pub auto_save_config_on_exit: bool,
+}
+
+impl Default for RuntimeAppConfig {
+    fn default() -> Self {
+        Self {
+            engine: EngineConfig::default(),
+            keybindings: KeybindingsConfig::default(),
+            messaging: MessagingConfig::default(),
+            resources: ResourcesConfig::default(),
+            tracing: TracingConfig::default(),
+            ui: UiConfig::default(),
+            auto_save_config_on_exit: true,
+        }
+    }
 }
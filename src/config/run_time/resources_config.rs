@@ -1,9 +1,35 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(default)]
 pub struct ResourcesConfig {
     pub resources_path: String,
+    /// The subfolders below are all relative to the [resources_path](ResourcesConfig::resources_path), see [ResourceKind](crate::resources::resource_manager::ResourceKind)
     pub fonts_path: String,
+    /// Where files the app generates (e.g. exported frame times) are written to, relative to the [resources_path](ResourcesConfig::resources_path)
+    pub output_path: String,
+    /// Where theme presets are stored, relative to the [resources_path](ResourcesConfig::resources_path)
+    pub themes_path: String,
+    /// Where scenes are stored, relative to the [resources_path](ResourcesConfig::resources_path). Not used yet
+    pub scenes_path: String,
+    /// Where textures are stored, relative to the [resources_path](ResourcesConfig::resources_path). Not used yet
+    pub textures_path: String,
+    /// Where meshes are stored, relative to the [resources_path](ResourcesConfig::resources_path). Not used yet
+    pub meshes_path: String,
+    /// Where environment maps are stored, relative to the [resources_path](ResourcesConfig::resources_path). Not used yet
+    pub environment_maps_path: String,
+    /// Where shaders are stored, relative to the [resources_path](ResourcesConfig::resources_path). Not used yet
+    pub shaders_path: String,
+    /// Where screenshots of the UI are saved to, relative to the [resources_path](ResourcesConfig::resources_path)
+    pub screenshots_path: String,
+    /// Where error reports saved from the errors popup are written to, relative to the [resources_path](ResourcesConfig::resources_path)
+    pub crash_reports_path: String,
+    /// Where [imgui] saves its settings (window positions, docking layout, etc.), relative to the [resources_path](ResourcesConfig::resources_path)
+    pub imgui_settings_path: String,
+    /// Where [imgui] writes its log (when logging is started), relative to the [resources_path](ResourcesConfig::resources_path)
+    pub imgui_log_path: String,
+    /// Whether resources should be automatically reloaded when their files change. Mostly useful while developing
+    pub hot_reload: bool,
 }
 
 impl ResourcesConfig {
@@ -18,6 +44,18 @@ impl Default for ResourcesConfig {
         Self {
             resources_path: "app_resources".into(),
             fonts_path: "fonts".into(),
+            output_path: "output".into(),
+            themes_path: "themes".into(),
+            scenes_path: "scenes".into(),
+            textures_path: "textures".into(),
+            meshes_path: "meshes".into(),
+            environment_maps_path: "environment_maps".into(),
+            shaders_path: "shaders".into(),
+            screenshots_path: "screenshots".into(),
+            crash_reports_path: "crash_reports".into(),
+            imgui_settings_path: "imgui.ini".into(),
+            imgui_log_path: "imgui_log.txt".into(),
+            hot_reload: false,
         }
     }
 }
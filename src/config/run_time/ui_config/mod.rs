@@ -3,6 +3,7 @@
 //! Contains UI configuration fields
 use frame_info_config::FrameInfoConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use theme::Theme;
 
 mod frame_info_config;
@@ -11,10 +12,21 @@ pub mod theme_ext;
 
 // Base configuration struct that contains options that configure the entire app
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct UiConfig {
     /// Oversampling font should help improve text rendering at expense of larger font atlas texture.
     /// Personally, I can't tell the difference
     pub font_oversampling: i32,
+    /// Size of the UI font (in logical pixels)
+    pub font_size: f32,
+    /// How much the font size changes by when using the increase/decrease font size keybindings
+    pub font_size_step: f32,
+    /// The maximum number of (unique) errors that will be stored for displaying in the errors popup. Older errors are removed first
+    pub max_displayed_errors: usize,
+    /// Which windows were open, by their [config_key](crate::ui::ui_data::WindowId::config_key), so they're restored when the app is next launched
+    ///
+    /// Windows that aren't in here use their default state. Kept up to date by the UI thread whenever a window is opened or closed
+    pub open_windows: BTreeMap<String, bool>,
     /// Colour arrays used for the UI
     pub colours: Theme,
 
@@ -25,6 +37,10 @@ impl Default for UiConfig {
     fn default() -> Self {
         Self {
             font_oversampling: 1,
+            font_size: 20.0,
+            font_size_step: 2.0,
+            max_displayed_errors: 50,
+            open_windows: BTreeMap::new(),
             colours: Theme::default(),
             frame_info: FrameInfoConfig::default(),
         }
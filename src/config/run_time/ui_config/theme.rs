@@ -11,12 +11,106 @@ pub struct Theme {
     pub severity: SeverityColours,
 }
 
+impl Theme {
+    /// Themes that are built into the app, so are always available as presets (along with any in the themes folder)
+    pub fn builtin_presets() -> [(&'static str, Theme); 3] {
+        [("Dark", Theme::default()), ("Light", Theme::light()), ("High Contrast", Theme::high_contrast())]
+    }
+
+    /// A theme with dark text on a light background
+    pub fn light() -> Self {
+        Self {
+            text: TextColours {
+                normal: [0.0, 0.0, 0.0, 1.0].into(),       // Black
+                subtle: [0.3, 0.3, 0.3, 1.0].into(),       // Dark grey
+                accent: [0.26, 0.59, 0.98, 1.0].into(),    // Bright blue
+                background: [0.94, 0.94, 0.94, 1.0].into(), // Off-white
+            },
+            value: ValueColours {
+                level_trace: [0.55, 0.2, 0.75, 1.0].into(), // Purple
+                level_debug: [0.15, 0.3, 0.8, 1.0].into(),  // Blue
+                level_info: [0.1, 0.55, 0.1, 1.0].into(),   // Green
+                level_warn: [0.8, 0.5, 0.0, 1.0].into(),    // Dark yellow
+                level_error: [0.8, 0.0, 0.0, 1.0].into(),   // Dark red
+
+                tracing_event_name: [0.75, 0.1, 0.1, 1.0].into(),        // Red
+                tracing_event_field_name: [0.1, 0.25, 0.75, 1.0].into(), // Blue
+                tracing_event_field_value: [0.2, 0.4, 0.8, 1.0].into(),  // Lighter blue
+
+                function_name: [0.0, 0.5, 0.45, 1.0].into(), // Dark cyan
+                file_location: [0.1, 0.25, 0.8, 1.0].into(), // Blue, hyperlink style
+
+                error_message: [0.8, 0.1, 0.1, 1.0].into(), // Red
+
+                value_label: [0.1, 0.1, 0.2, 1.0].into(), // Almost black, slightly blue
+
+                misc_value: [0.1, 0.55, 0.1, 1.0].into(),    // Green
+                missing_value: [0.6, 0.6, 0.6, 1.0].into(),  // Light grey
+                symbol: [0.3, 0.3, 0.25, 1.0].into(),        // Dark grey
+                number: [0.0, 0.5, 0.35, 1.0].into(),        // Dark green with a tint of blue
+            },
+            severity: SeverityColours {
+                good: [0.1, 0.6, 0.1, 1.0].into(),    // Green
+                neutral: [0.4, 0.4, 0.4, 1.0].into(), // Grey
+                note: [0.2, 0.3, 0.7, 1.0].into(),    // Blue
+                warning: [0.8, 0.4, 0.0, 1.0].into(), // Dark amber
+                very_bad: [0.8, 0.0, 0.0, 1.0].into(), // Dark red
+            },
+        }
+    }
+
+    /// A theme with bright, saturated colours on a pure black background, to be as readable as possible
+    pub fn high_contrast() -> Self {
+        Self {
+            text: TextColours {
+                normal: [1.0, 1.0, 1.0, 1.0].into(),     // White
+                subtle: [0.9, 0.9, 0.9, 1.0].into(),     // Very light grey
+                accent: [1.0, 1.0, 0.0, 1.0].into(),     // Yellow
+                background: [0.0, 0.0, 0.0, 1.0].into(), // Black
+            },
+            value: ValueColours {
+                level_trace: [1.0, 0.5, 1.0, 1.0].into(), // Pink
+                level_debug: [0.4, 0.7, 1.0, 1.0].into(), // Light blue
+                level_info: [0.3, 1.0, 0.3, 1.0].into(),  // Green
+                level_warn: [1.0, 1.0, 0.0, 1.0].into(),  // Yellow
+                level_error: [1.0, 0.2, 0.2, 1.0].into(), // Red
+
+                tracing_event_name: [1.0, 0.4, 0.4, 1.0].into(),        // Pale red
+                tracing_event_field_name: [0.4, 0.8, 1.0, 1.0].into(),  // Light blue
+                tracing_event_field_value: [0.7, 0.9, 1.0, 1.0].into(), // Very light blue
+
+                function_name: [0.0, 1.0, 1.0, 1.0].into(), // Cyan
+                file_location: [0.4, 0.7, 1.0, 1.0].into(), // Light blue
+
+                error_message: [1.0, 0.3, 0.3, 1.0].into(), // Red
+
+                value_label: [1.0, 1.0, 1.0, 1.0].into(), // White
+
+                misc_value: [0.3, 1.0, 0.3, 1.0].into(),    // Green
+                missing_value: [0.6, 0.6, 0.6, 1.0].into(), // Grey
+                symbol: [1.0, 1.0, 1.0, 1.0].into(),        // White
+                number: [0.3, 1.0, 0.8, 1.0].into(),        // Green with a tint of blue
+            },
+            severity: SeverityColours {
+                good: [0.0, 1.0, 0.0, 1.0].into(),       // Green
+                neutral: [0.85, 0.85, 0.85, 1.0].into(), // Light grey
+                note: [0.5, 0.7, 1.0, 1.0].into(),       // Light blue
+                warning: [1.0, 0.8, 0.0, 1.0].into(),    // Amber
+                very_bad: [1.0, 0.0, 0.0, 1.0].into(),   // Red
+            },
+        }
+    }
+}
+
 /// Theme struct for general text colours that would be used with most normal (non-specialised) text
 #[derive(Debug, Copy, Clone, Serialize, Deserialize,  PartialEq)]
+#[serde(default)]
 pub struct TextColours {
     pub normal: Colour,
     pub subtle: Colour,
     pub accent: Colour,
+    /// Colour of the window backgrounds that the text is drawn on top of
+    pub background: Colour,
 }
 impl Default for TextColours {
     fn default() -> Self {
@@ -24,6 +118,7 @@ impl Default for TextColours {
             normal: [1.0, 1.0, 1.0, 1.0].into(),       // Full white
             subtle: [0.8, 0.8, 0.8, 1.0].into(),       // Slightly grey
             accent: [0.223, 0.287, 0.783, 1.0].into(), // Darkish pale blue
+            background: [0.06, 0.06, 0.06, 0.94].into(), // Almost black, same as Dear ImGui's default
         }
     }
 }
@@ -1,10 +1,12 @@
-use std::sync::{Arc, Barrier, Mutex};
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Barrier};
 use std::thread;
 use std::time::Duration;
 
 use crate::FallibleFn;
 use multiqueue2::{BroadcastReceiver, BroadcastSender};
 use nameof::name_of;
+use parking_lot::{Mutex, MutexGuard};
 use tracing::{debug, debug_span, info_span, trace, trace_span};
 
 use crate::helper::logging::event_targets::*;
@@ -13,11 +15,48 @@ use crate::program::thread_messages::ThreadMessage::{Engine, Program, Ui};
 use crate::program::thread_messages::*;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
-pub struct EngineData {}
+pub struct EngineData {
+    /// What the engine is currently doing, shown to the user in the status bar
+    pub status: EngineStatus,
+    /// How many times the engine's global loop has run, so it's possible to see the engine being [stepped](EngineThreadMessage::Step)
+    pub iterations: usize,
+}
+
+/// What the engine is currently doing
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum EngineStatus {
+    /// Not rendering anything
+    Idle,
+    /// Rendering a frame, `completed` out of `total` samples are done
+    Rendering { completed: u32, total: u32 },
+    /// The last render finished, and took `took` to complete
+    Finished { took: Duration },
+    /// The engine loop has been paused by the user, and won't do anything until it's resumed
+    Paused,
+}
+
+impl Display for EngineStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EngineStatus::Idle => f.write_str("Idle"),
+            EngineStatus::Rendering { completed, total } => {
+                let percent = if *total == 0 { 0.0 } else { *completed as f32 / *total as f32 * 100.0 };
+                write!(f, "Rendering ({completed}/{total}, {percent:.0}%)")
+            }
+            EngineStatus::Finished { took } => write!(f, "Finished (took {})", humantime::format_duration(round_to_millis(*took))),
+            EngineStatus::Paused => f.write_str("Paused"),
+        }
+    }
+}
+
+/// Drops everything smaller than a millisecond, since nobody cares about the nanoseconds a render took
+pub fn round_to_millis(duration: Duration) -> Duration {
+    Duration::from_millis(duration.as_millis() as u64)
+}
 
 pub(crate) fn engine_thread(
     thread_start_barrier: Arc<Barrier>,
-    _program_data_wrapped: Arc<Mutex<ProgramData>>,
+    program_data_wrapped: Arc<Mutex<ProgramData>>,
     message_sender: BroadcastSender<ThreadMessage>,
     message_receiver: BroadcastReceiver<ThreadMessage>,
 ) -> FallibleFn {
@@ -31,17 +70,47 @@ pub(crate) fn engine_thread(
         span_sync_thread_start.exit();
     }
 
+    // The engine doesn't render anything yet, so each iteration just pretends to work for a second
+    run_engine_loop(&program_data_wrapped, &message_receiver, Duration::from_secs(1))?;
+
+    // If we get to here, it's time to exit the thread and shutdown
+    debug!(target: THREAD_DEBUG_GENERAL, "engine thread exiting");
+
+    debug!(target: THREAD_DEBUG_MESSENGER_LIFETIME, "unsubscribing message receiver");
+    message_receiver.unsubscribe();
+    debug!(target: THREAD_DEBUG_MESSENGER_LIFETIME, "unsubscribing message sender");
+    message_sender.unsubscribe();
+
+    debug!(target: THREAD_DEBUG_GENERAL, "engine thread done");
+    span_engine_thread.exit();
+    Ok(())
+}
+
+/// The engine's main loop: does (pretend) work for `work_duration` every iteration, and processes the messages sent to the engine in between
+///
+/// Returns once the engine is told to exit
+fn run_engine_loop(program_data_wrapped: &Mutex<ProgramData>, message_receiver: &BroadcastReceiver<ThreadMessage>, work_duration: Duration) -> FallibleFn {
+    // What the engine was doing before it was paused, so it can be restored when it's resumed. [None] if not paused
+    let mut status_before_pause: Option<EngineStatus> = None;
+
     let span_global_loop = debug_span!(target: ENGINE_TRACE_GLOBAL_LOOP, "'global").entered();
     'global: for global_iter in 0usize.. {
         let span_global_loop_inner = trace_span!(target: ENGINE_TRACE_GLOBAL_LOOP, "inner", global_iter).entered();
 
         // Pretend we're doing work here
-        thread::sleep(Duration::from_secs(1));
+        thread::sleep(work_duration);
+        update_engine_data(program_data_wrapped, |data| data.iterations = global_iter + 1);
 
         let span_process_messages = trace_span!(target: THREAD_TRACE_MESSAGE_LOOP, "process_messages").entered();
         // Loops until [command_receiver] is empty (tries to 'flush' out all messages)
+        // While paused, this blocks waiting for the next message instead, so we stay in here (without spinning) until we're resumed or told to exit
         'process_messages: loop {
-            if let Some(message) = receive_message(&message_receiver)? {
+            let maybe_message = if status_before_pause.is_some() {
+                Some(wait_for_message(message_receiver)?)
+            } else {
+                receive_message(message_receiver)?
+            };
+            if let Some(message) = maybe_message {
                 match message {
                     Ui(_) | Program(_) => {
                         message.ignore();
@@ -54,6 +123,31 @@ pub(crate) fn engine_thread(
                                 debug!(target: THREAD_DEBUG_GENERAL, "got exit message for engine thread");
                                 break 'global;
                             }
+                            EngineThreadMessage::Pause => {
+                                if status_before_pause.is_none() {
+                                    debug!(target: ENGINE_DEBUG_GENERAL, "pausing engine");
+                                    status_before_pause = Some(update_engine_data(program_data_wrapped, |data| std::mem::replace(&mut data.status, EngineStatus::Paused)));
+                                } else {
+                                    debug!(target: ENGINE_DEBUG_GENERAL, "engine already paused");
+                                }
+                            }
+                            EngineThreadMessage::Resume => match status_before_pause.take() {
+                                Some(status) => {
+                                    debug!(target: ENGINE_DEBUG_GENERAL, ?status, "resuming engine");
+                                    update_engine_data(program_data_wrapped, |data| data.status = status);
+                                }
+                                None => debug!(target: ENGINE_DEBUG_GENERAL, "engine not paused, can't resume"),
+                            },
+                            EngineThreadMessage::Step => {
+                                if status_before_pause.is_some() {
+                                    // Leaving the message loop lets the global loop run once more, then we end up back in here waiting for messages
+                                    // Any other steps are left in the queue, so each one gets its own iteration
+                                    debug!(target: ENGINE_DEBUG_GENERAL, "stepping engine");
+                                    break 'process_messages;
+                                } else {
+                                    debug!(target: ENGINE_DEBUG_GENERAL, "engine not paused, ignoring step");
+                                }
+                            }
                         }
                     }
                 }
@@ -69,15 +163,66 @@ pub(crate) fn engine_thread(
     }
     span_global_loop.exit();
 
-    // If we get to here, it's time to exit the thread and shutdown
-    debug!(target: THREAD_DEBUG_GENERAL, "engine thread exiting");
+    Ok(())
+}
 
-    debug!(target: THREAD_DEBUG_MESSENGER_LIFETIME, "unsubscribing message receiver");
-    message_receiver.unsubscribe();
-    debug!(target: THREAD_DEBUG_MESSENGER_LIFETIME, "unsubscribing message sender");
-    message_sender.unsubscribe();
+/// Runs `func` on the [EngineData] in the [ProgramData], holding the lock for as little time as possible
+fn update_engine_data<T>(program_data_wrapped: &Mutex<ProgramData>, func: impl FnOnce(&mut EngineData) -> T) -> T {
+    let mut program_data = program_data_wrapped.lock();
+    let result = func(&mut program_data.engine_data);
+    MutexGuard::unlock_fair(program_data);
+    result
+}
 
-    debug!(target: THREAD_DEBUG_GENERAL, "engine thread done");
-    span_engine_thread.exit();
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::ui_data::UiData;
+    use color_eyre::eyre;
+    use multiqueue2::broadcast_queue;
+    use pretty_assertions::assert_eq;
+
+    /// Runs the engine loop (without any pretend work) until it's told to exit, after it has received all the `messages`, and returns the engine data it finished with
+    fn run_with_messages(messages: impl IntoIterator<Item = EngineThreadMessage>) -> eyre::Result<EngineData> {
+        let (sender, receiver) = broadcast_queue::<ThreadMessage>(100);
+        for message in messages.into_iter().chain([EngineThreadMessage::ExitEngineThread]) {
+            send_message(Engine(message), &sender)?;
+        }
+        let program_data = Mutex::new(ProgramData {
+            ui_data: UiData::default(),
+            engine_data: EngineData {
+                status: EngineStatus::Idle,
+                iterations: 0,
+            },
+        });
+        run_engine_loop(&program_data, &receiver, Duration::ZERO)?;
+        let engine_data = program_data.lock().engine_data;
+        Ok(engine_data)
+    }
+
+    #[test]
+    fn steps_advance_one_iteration_each() -> FallibleFn {
+        const STEPS: usize = 5;
+        let engine_data = run_with_messages([EngineThreadMessage::Pause].into_iter().chain([EngineThreadMessage::Step; STEPS]))?;
+        // The first iteration is the one that received the pause
+        assert_eq!(engine_data.iterations, 1 + STEPS);
+        assert_eq!(engine_data.status, EngineStatus::Paused);
+        Ok(())
+    }
+
+    #[test]
+    fn steps_ignored_when_not_paused() -> FallibleFn {
+        let engine_data = run_with_messages([EngineThreadMessage::Step; 3])?;
+        assert_eq!(engine_data.iterations, 1);
+        assert_eq!(engine_data.status, EngineStatus::Idle);
+        Ok(())
+    }
+
+    #[test]
+    fn resume_restores_status() -> FallibleFn {
+        let engine_data = run_with_messages([EngineThreadMessage::Pause, EngineThreadMessage::Step, EngineThreadMessage::Resume])?;
+        assert_eq!(engine_data.iterations, 2);
+        assert_eq!(engine_data.status, EngineStatus::Idle);
+        Ok(())
+    }
 }
@@ -1,6 +1,11 @@
-use color_eyre::{eyre, Report};
+use color_eyre::eyre::WrapErr;
+use color_eyre::{eyre, Help, Report};
 use std::env;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::FallibleFn;
 
 /// Gets the directory of the current app executable
 pub fn app_current_directory() -> eyre::Result<PathBuf> {
@@ -18,3 +23,54 @@ pub fn app_current_directory() -> eyre::Result<PathBuf> {
         },
     )?
 }
+
+/// Opens a file in the user's editor, optionally at a given line
+///
+/// If the `EDITOR` environment variable is set, it is launched as `$EDITOR +<line> <file>` (which most terminal editors understand).
+/// Otherwise, we fall back to asking the OS to open the file with whatever the default program is (the line number is lost in that case)
+///
+/// The spawned process is not waited on, so this returns as soon as the editor has been launched
+pub fn open_file_in_editor(file: &Path, line: Option<u32>) -> FallibleFn {
+    let mut command = match env::var("EDITOR") {
+        Ok(editor) if !editor.trim().is_empty() => {
+            let mut command = Command::new(editor.trim());
+            if let Some(line) = line {
+                command.arg(format!("+{line}"));
+            }
+            command.arg(file);
+            command
+        }
+        // No editor set, so let the OS decide
+        _ => os_open_command(file.as_os_str()),
+    };
+
+    command
+        .spawn()
+        .wrap_err("failed to launch editor process")
+        .with_note(|| format!("command was {command:?}"))
+        .suggestion("try setting the `EDITOR` environment variable to your preferred editor")?;
+    Ok(())
+}
+
+/// Opens a URL in the user's default browser
+pub fn open_url(url: &str) -> FallibleFn {
+    let mut command = os_open_command(url.as_ref());
+    command.spawn().wrap_err("failed to open url").with_note(|| format!("command was {command:?}"))?;
+    Ok(())
+}
+
+/// Creates a command that asks the OS to open the `target` (a file or URL) with whatever the default program for it is
+fn os_open_command(target: &OsStr) -> Command {
+    let mut command;
+    if cfg!(target_os = "windows") {
+        command = Command::new("cmd");
+        // The empty string is the title of the window, without it `start` treats a quoted path as the title
+        command.args(["/C", "start", ""]);
+    } else if cfg!(target_os = "macos") {
+        command = Command::new("open");
+    } else {
+        command = Command::new("xdg-open");
+    }
+    command.arg(target);
+    command
+}
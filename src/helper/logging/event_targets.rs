@@ -43,6 +43,7 @@ target!(UI_DEBUG_GENERAL, r"General debug events relating to the UI");
 
 // ===== Engine =====
 target!(ENGINE_TRACE_GLOBAL_LOOP, r"poll events when the engine does it's global loop");
+target!(ENGINE_DEBUG_GENERAL, r"General debug events relating to the engine, like it being paused or resumed");
 
 // ===== Program/Main =====
 target!(MAIN_DEBUG_GENERAL, r#"main.rs general logs, like initialising something"#);
@@ -80,7 +81,7 @@ target!(
 target!(
     THREAD_TRACE_MUTEX_SYNC,
     r#"
-    Events that are logged when trying to synchronise between threads using [std::sync::mutex::Mutex]
+    Events that are logged when trying to synchronise between threads using [parking_lot::Mutex]
 "#
 );
 
@@ -150,3 +151,42 @@ target!(
     Use these where you would normally panic (panic=bad, kapishe?)
 "
 );
+
+// ===== Lists =====
+/// Every target defined in this file, in the order they're defined. New targets need adding here too, or they can't be picked in the config UI
+pub const ALL_TARGETS: [&str; 29] = [
+    UI_TRACE_EVENT_LOOP,
+    UI_TRACE_RENDER,
+    UI_TRACE_BUILD_INTERFACE,
+    UI_DEBUG_USER_INTERACTION,
+    UI_TRACE_USER_INPUT,
+    UI_TRACE_MISC_PERFRAME_CALCULATIONS,
+    UI_DEBUG_GENERAL,
+    ENGINE_TRACE_GLOBAL_LOOP,
+    ENGINE_DEBUG_GENERAL,
+    MAIN_DEBUG_GENERAL,
+    PROGRAM_INFO_LIFECYCLE,
+    PROGRAM_DEBUG_GENERAL,
+    PROGRAM_TRACE_THREAD_STATUS_POLL,
+    PROGRAM_TRACE_GLOBAL_LOOP,
+    THREAD_TRACE_MESSAGE_IGNORED,
+    THREAD_DEBUG_MESSAGE_RECEIVED,
+    THREAD_DEBUG_MESSAGE_SEND,
+    THREAD_DEBUG_GENERAL,
+    THREAD_DEBUG_MESSENGER_LIFETIME,
+    THREAD_TRACE_MESSAGE_LOOP,
+    THREAD_TRACE_MUTEX_SYNC,
+    RESOURCES_DEBUG_LOAD,
+    RESOURCES_WARNING_NON_FATAL,
+    FONT_MANAGER_TRACE_FONT_LOAD,
+    DATA_DEBUG_DUMP_OBJECT,
+    GENERAL_WARNING_NON_FATAL,
+    GENERAL_ERROR_FATAL,
+    DOMINO_EFFECT_FAILURE,
+    REALLY_FUCKING_BAD_UNREACHABLE,
+];
+
+/// Targets that are always logged, ignoring the [target filters](crate::config::run_time::tracing_config::TracingConfig::target_filters)
+///
+/// Filtering happens while logging, so if a problem with the config was logged as a warning, checking the filters for that warning would recurse
+pub const UNFILTERABLE_TARGETS: [&str; 4] = [GENERAL_WARNING_NON_FATAL, GENERAL_ERROR_FATAL, REALLY_FUCKING_BAD_UNREACHABLE, DOMINO_EFFECT_FAILURE];
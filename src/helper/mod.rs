@@ -2,3 +2,6 @@
 pub mod logging;
 pub mod file_helper;
 pub mod panic_pill;
+pub mod ring_buffer;
+pub mod rng;
+pub mod types;
@@ -10,7 +10,7 @@ pub(crate) fn red_or_blue_pill() {
     // Got panics? We've got a pill for that!
     //
     // Custom panic hook that prints a log message and quits the whole process
-    //TODO: Check out eyre::HookBuilder (as per https://github.com/yaahc/color-eyre/pull/51)
+    // Wraps the hook [init_eyre()](crate::init_eyre) installed, so the panic report is still printed first
     let old_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
         old_hook(panic_info);
@@ -0,0 +1,172 @@
+//! A fixed-capacity ring buffer, for keeping the most recent values of something (e.g. frame times) without shifting the whole buffer on every push
+
+use std::iter::{Chain, Rev};
+use std::slice::Iter;
+
+/// Fixed-capacity FIFO buffer. Once it's full, pushing a value overwrites the oldest one
+///
+/// Values are indexed and iterated *most recent first*, since that's how everything that uses it displays them
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    /// The stored values, oldest first starting from [head](RingBuffer::head) (wrapping around)
+    data: Vec<T>,
+    /// Index in [data](RingBuffer::data) that the next value will be written to. Once the buffer is full, this is also the oldest value
+    head: usize,
+    /// Maximum number of values stored
+    capacity: usize,
+}
+
+impl<T: Copy> RingBuffer<T> {
+    /// Creates a new empty buffer, that can hold up to `capacity` values
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            head: 0,
+            capacity,
+        }
+    }
+
+    /// Pushes a new value into the buffer, overwriting the oldest value if the buffer is full. `O(1)`
+    pub fn push(&mut self, value: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.data.len() < self.capacity {
+            self.data.push(value);
+        } else {
+            self.data[self.head] = value;
+        }
+        self.head = (self.head + 1) % self.capacity;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Changes how many values the buffer can hold. If it shrinks, the oldest values are dropped
+    ///
+    /// This has to reorder the values so it's `O(n)`, but does nothing if the capacity is unchanged
+    pub fn set_capacity(&mut self, capacity: usize) {
+        if capacity == self.capacity {
+            return;
+        }
+        // Rebuild oldest first, keeping only the most recent values
+        let mut data: Vec<T> = self.iter().take(capacity).copied().collect();
+        data.reverse();
+        data.reserve_exact(capacity.saturating_sub(data.len()));
+        self.head = if capacity == 0 { 0 } else { data.len() % capacity };
+        self.data = data;
+        self.capacity = capacity;
+    }
+
+    /// The stored values as (at most) two contiguous slices, *oldest first*. The first slice holds the older values
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (newer, older) = self.data.split_at(self.head);
+        (older, newer)
+    }
+
+    /// Iterates over the values, most recent first
+    pub fn iter(&self) -> Chain<Rev<Iter<'_, T>>, Rev<Iter<'_, T>>> {
+        let (older, newer) = self.as_slices();
+        newer.iter().rev().chain(older.iter().rev())
+    }
+
+    /// Copies (up to) the `count` most recent values into `scratch` (most recent first), replacing its contents
+    ///
+    /// This is for when a single contiguous slice is needed (e.g. for plotting), reusing `scratch` so it doesn't allocate every time
+    pub fn copy_recent_into(&self, count: usize, scratch: &mut Vec<T>) {
+        scratch.clear();
+        scratch.extend(self.iter().take(count).copied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RingBuffer;
+    use pretty_assertions::assert_eq;
+
+    fn buffer_with(capacity: usize, values: impl IntoIterator<Item = i32>) -> RingBuffer<i32> {
+        let mut buffer = RingBuffer::new(capacity);
+        values.into_iter().for_each(|value| buffer.push(value));
+        buffer
+    }
+
+    fn contents(buffer: &RingBuffer<i32>) -> Vec<i32> {
+        buffer.iter().copied().collect()
+    }
+
+    #[test]
+    fn iterates_most_recent_first_before_filling() {
+        let buffer = buffer_with(4, 1..=3);
+        assert_eq!(contents(&buffer), vec![3, 2, 1]);
+        // Hasn't wrapped yet, so everything is in one slice
+        assert_eq!(buffer.as_slices(), (&[][..], &[1, 2, 3][..]));
+    }
+
+    #[test]
+    fn wraps_around_overwriting_oldest() {
+        let buffer = buffer_with(4, 1..=6);
+        assert_eq!(contents(&buffer), vec![6, 5, 4, 3]);
+        // Oldest first, split where the buffer wrapped
+        assert_eq!(buffer.as_slices(), (&[3, 4][..], &[5, 6][..]));
+    }
+
+    #[test]
+    fn wraps_around_many_times() {
+        let buffer = buffer_with(3, 1..=100);
+        assert_eq!(contents(&buffer), vec![100, 99, 98]);
+    }
+
+    #[test]
+    fn zero_capacity_stores_nothing() {
+        let buffer = buffer_with(0, 1..=3);
+        assert!(buffer.is_empty());
+        assert_eq!(contents(&buffer), vec![]);
+    }
+
+    #[test]
+    fn shrinking_keeps_most_recent() {
+        let mut buffer = buffer_with(5, 1..=7);
+        buffer.set_capacity(3);
+        assert_eq!(contents(&buffer), vec![7, 6, 5]);
+        // Keeps wrapping correctly at the new size
+        buffer.push(8);
+        assert_eq!(contents(&buffer), vec![8, 7, 6]);
+    }
+
+    #[test]
+    fn growing_keeps_everything() {
+        let mut buffer = buffer_with(3, 1..=5);
+        buffer.set_capacity(5);
+        assert_eq!(contents(&buffer), vec![5, 4, 3]);
+        buffer.push(6);
+        buffer.push(7);
+        assert_eq!(contents(&buffer), vec![7, 6, 5, 4, 3]);
+        buffer.push(8);
+        assert_eq!(contents(&buffer), vec![8, 7, 6, 5, 4]);
+    }
+
+    #[test]
+    fn resizing_to_zero_and_back() {
+        let mut buffer = buffer_with(3, 1..=5);
+        buffer.set_capacity(0);
+        assert!(buffer.is_empty());
+        buffer.push(6);
+        assert!(buffer.is_empty());
+        buffer.set_capacity(2);
+        buffer.push(7);
+        buffer.push(8);
+        buffer.push(9);
+        assert_eq!(contents(&buffer), vec![9, 8]);
+    }
+
+    #[test]
+    fn copy_recent_into_replaces_scratch() {
+        let buffer = buffer_with(4, 1..=6);
+        let mut scratch = vec![42; 10];
+        buffer.copy_recent_into(2, &mut scratch);
+        assert_eq!(scratch, vec![6, 5]);
+        buffer.copy_recent_into(100, &mut scratch);
+        assert_eq!(scratch, vec![6, 5, 4, 3]);
+    }
+}
@@ -0,0 +1,21 @@
+//! Random number generation that can be made deterministic, by setting the [seed](crate::config::run_time::engine_config::EngineConfig::seed) in the config
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::config::read_config_value;
+
+/// The random number generator used throughout the app.
+///
+/// Has to be a specific algorithm rather than [rand::rngs::StdRng], since that one is allowed to change between versions of [rand], which would change what a seed renders
+pub type AppRng = ChaCha8Rng;
+
+/// Creates a new random number generator.
+///
+/// If a seed is set in the config, the RNG is seeded with that, so every RNG created gives the same numbers. Otherwise it's seeded randomly
+pub fn new_rng() -> AppRng {
+    match read_config_value(|config| config.runtime.engine.seed) {
+        Some(seed) => AppRng::seed_from_u64(seed),
+        None => AppRng::from_entropy(),
+    }
+}
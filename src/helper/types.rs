@@ -0,0 +1,14 @@
+//! Type aliases that are used all over the app
+
+use std::thread::JoinHandle;
+
+use color_eyre::eyre;
+
+/// General type alias for a function that may fail: `eyre::Result<()>`
+pub type FallibleFn = eyre::Result<()>;
+
+/// What the app's threads return when they finish
+pub type ThreadReturn = FallibleFn;
+
+/// Handle to one of the app's threads, for joining it once it's done
+pub type ThreadHandle = JoinHandle<ThreadReturn>;
@@ -2,10 +2,12 @@
 #![warn(clippy::all)]
 
 //! # A little test raytracer project
-use std::io;
+use std::fmt::{Display, Formatter};
+use std::{io, panic};
 
-use crate::config::{read_config_value, save_config_to_disk};
-use color_eyre::eyre;
+use crate::config::read_config_value;
+use color_eyre::config::{HookBuilder, IssueKind};
+use color_eyre::{Help, SectionExt};
 use tracing::level_filters::LevelFilter;
 use tracing::*;
 use tracing_error::ErrorLayer;
@@ -25,13 +27,13 @@ mod program;
 mod resources;
 mod ui;
 
-/// General type alias for a function that may fail: `eyre::Result<()>`
-pub type FallibleFn = eyre::Result<()>;
+/// Re-exported so it can still be used as `crate::FallibleFn`, since it's used almost everywhere
+pub use helper::types::FallibleFn;
 
 /// Main entrypoint for the program
 ///
 /// Handles the important setup before handing control over to the actual program:
-/// * Initialises [eyre] (for panic/error handling)
+/// * Initialises [eyre](color_eyre) (for panic/error handling)
 /// * Initialises [tracing] (for logging)
 /// * TODO: Processes command-line arguments
 /// * Runs the [program] for real
@@ -48,14 +50,13 @@ fn main() -> FallibleFn {
     debug!(target: MAIN_DEBUG_GENERAL, ?args, ?args_os, "command line");
     debug!(target: MAIN_DEBUG_GENERAL, "core init done");
 
+    if let Err(report) = program::register_shutdown_handler() {
+        warn!(target: GENERAL_WARNING_NON_FATAL, report = %format_report_display(&report), "Ctrl+C will kill the app without saving");
+    }
+
     info!(target: PROGRAM_INFO_LIFECYCLE, "starting program");
     let ret = program::run();
 
-    debug!(target: MAIN_DEBUG_GENERAL, "saving config to disk");
-    if let Err(error) = save_config_to_disk() {
-        warn!(target: GENERAL_WARNING_NON_FATAL, report = %format_report_display(&error), "couldn't save config to disk")
-    }
-
     match ret {
         Ok(program_return_value) => {
             info!(target: PROGRAM_INFO_LIFECYCLE, ?program_return_value, "program completed successfully");
@@ -65,14 +66,48 @@ fn main() -> FallibleFn {
         Err(report) => {
             error!(target: PROGRAM_INFO_LIFECYCLE, report = format_report_display(&report), "program exited unsuccessfully");
             info!(target: PROGRAM_INFO_LIFECYCLE, "goodbye :(");
-            Err(report)
+            Err(report.section(build::build_info_text().header("Build:")).section(CurrentConfig.header("Config:")))
         }
     }
 }
 
-/// Initialises [eyre]. Called as part of the core init
+/// Initialises [eyre](color_eyre). Called as part of the core init
+///
+/// Panics get a link for opening an issue on the repository (with the build info filled in), and have the current config attached
 fn init_eyre() -> FallibleFn {
-    color_eyre::install()
+    let mut builder = HookBuilder::default()
+        .issue_url(concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new"))
+        // Errors are usually something wrong on the user's end (like a missing file), so only ask for issues when we've actually got a bug
+        .issue_filter(|kind| matches!(kind, IssueKind::Panic(_)))
+        .display_env_section(true)
+        .panic_section(PanicSection);
+    for (label, value) in build::build_info() {
+        builder = builder.add_issue_metadata(label, value);
+    }
+    builder.install()
+}
+
+/// Extra information shown when the app panics
+struct PanicSection;
+
+impl Display for PanicSection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let instructions = "Please open an issue using the link below, and describe what you were doing when the app crashed. The config below may help too";
+        write!(f, "{}\n{}", instructions.header("Reporting:"), CurrentConfig.header("Config:"))
+    }
+}
+
+/// Displays the current config. Read when it's displayed (not when it's attached), so it's as up-to-date as possible
+struct CurrentConfig;
+
+impl Display for CurrentConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Reading the config shouldn't panic, but if it somehow does, we'd rather lose the config than the whole report
+        match panic::catch_unwind(|| read_config_value(|config| config.clone())) {
+            Ok(config) => write!(f, "{config:#?}"),
+            Err(_) => f.write_str("<could not read config>"),
+        }
+    }
 }
 
 /// Initialises the [tracing] system. Called as part of the core init
@@ -102,7 +137,7 @@ fn init_tracing() -> FallibleFn {
             match target {
                 // If we encounter an error with the config, then we may try logging a warning while filtering a previous message
                 // This would recurse, so bypass and exit early if the target matches the warning/error targets
-                GENERAL_WARNING_NON_FATAL | GENERAL_ERROR_FATAL | REALLY_FUCKING_BAD_UNREACHABLE | DOMINO_EFFECT_FAILURE => true,
+                target if UNFILTERABLE_TARGETS.contains(&target) => true,
                 // Otherwise (default), scan the config
                 _ => {
                     let configured_targets = read_config_value(|config| config.runtime.tracing.target_filters.clone());
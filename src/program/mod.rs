@@ -1,7 +1,9 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
 use std::sync::mpsc::TrySendError::*;
-use std::sync::{Arc, Barrier, Mutex};
+use std::sync::{Arc, Barrier};
 use std::thread;
-use std::thread::JoinHandle;
 use std::time::Duration;
 
 use color_eyre::eyre::WrapErr;
@@ -9,15 +11,18 @@ use color_eyre::{eyre, Help, Report, SectionExt};
 use indoc::formatdoc;
 use multiqueue2::{broadcast_queue, BroadcastReceiver, BroadcastSender};
 use nameof::name_of;
-use tracing::{debug, debug_span, error, info, info_span, trace, trace_span};
+use parking_lot::Mutex;
+use tracing::{debug, debug_span, error, info, info_span, trace, trace_span, warn};
 
 use program_data::ProgramData;
 use ProgramThreadMessage::{QuitAppError, QuitAppNoError};
 use QuitAppNoErrorReason::QuitInteractionByUser;
 
+use crate::config::{config_file_path, config_load_failed, read_config_value, save_config_to};
 use crate::engine::*;
 use crate::helper::logging::event_targets::*;
 use crate::helper::logging::{dyn_panic_to_report, format_report_display, format_report_string};
+use crate::helper::types::{ThreadHandle, ThreadReturn};
 use crate::program::thread_messages::ThreadMessage::*;
 use crate::program::thread_messages::*;
 use crate::ui::ui_data::UiData;
@@ -28,8 +33,23 @@ use crate::FallibleFn;
 pub(crate) mod thread_messages;
 pub mod program_data;
 
-pub type ThreadReturn = FallibleFn;
-pub type ThreadHandle = JoinHandle<ThreadReturn>;
+/// Set when the OS asks us to quit (e.g. Ctrl+C or SIGTERM), see [register_shutdown_handler]. Checked by the 'global loop in [run]
+pub static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Registers a handler for SIGINT/SIGTERM (or the Windows console equivalents) that asks the program to shut down cleanly
+///
+/// Without this the process is killed straight away, so the threads aren't joined and the config isn't saved.
+/// If the signal is received again (e.g. the shutdown is stuck), the process exits immediately
+pub fn register_shutdown_handler() -> FallibleFn {
+    ctrlc::set_handler(|| {
+        if SHUTDOWN_REQUESTED.swap(true, Relaxed) {
+            warn!(target: PROGRAM_INFO_LIFECYCLE, "received shutdown signal again, exiting immediately");
+            std::process::exit(130);
+        }
+        info!(target: PROGRAM_INFO_LIFECYCLE, "received shutdown signal, quitting");
+    })
+    .wrap_err("could not register shutdown signal handler")
+}
 
 pub fn run() -> ThreadReturn {
     let span_run = info_span!(target: PROGRAM_INFO_LIFECYCLE, name_of!(run)).entered();
@@ -38,15 +58,18 @@ pub fn run() -> ThreadReturn {
     // Create new program 'instance'
     debug!(target: PROGRAM_DEBUG_GENERAL, "creating ProgramData");
     let program_data = ProgramData {
-        ui_data: UiData::default(),
-        engine_data: EngineData {},
+        ui_data: UiData::from_config(),
+        engine_data: EngineData {
+            status: EngineStatus::Idle,
+            iterations: 0,
+        },
     };
     debug!(target: PROGRAM_DEBUG_GENERAL, ?program_data);
 
     // Wrap the program data inside an Arc(Mutex(T))
     // This allows us to:
     // (Arc): Share a reference of the Mutex(ProgramData) across the threads safely
-    // (Mutex): Use that reference to give a single thread access to the ProgramData at one time
+    // (Mutex): Use that reference to give a single thread access to the ProgramData at one time (this is a [parking_lot::Mutex], so waiting threads are parked fairly and it can't be poisoned)
     debug!(target: PROGRAM_DEBUG_GENERAL, "wrapping program data for thread-safety");
     let program_data_wrapped = Arc::new(Mutex::new(program_data));
     debug!(target: PROGRAM_DEBUG_GENERAL, ?program_data_wrapped);
@@ -102,14 +125,19 @@ pub fn run() -> ThreadReturn {
         })
     })?;
 
-    let poll_interval = Duration::from_millis(1000);
     // Should loop until program exits
-    debug!(target: PROGRAM_DEBUG_GENERAL, ?poll_interval, "entering 'global loop");
+    debug!(target: PROGRAM_DEBUG_GENERAL, "entering 'global loop");
 
     let span_global_loop = debug_span!(target: PROGRAM_DEBUG_GENERAL, "'global").entered();
     'global: for global_iter in 0usize.. {
         let span_global_loop_inner = trace_span!(target: PROGRAM_TRACE_GLOBAL_LOOP, "inner", %global_iter).entered();
 
+        if SHUTDOWN_REQUESTED.load(Relaxed) {
+            debug!(target: PROGRAM_DEBUG_GENERAL, "shutdown requested by signal");
+            handle_user_quit(msg_sender, msg_receiver, threads, auto_save_config_path().as_deref())?;
+            break 'global;
+        }
+
         // Process any messages we might have from the other threads
         let span_process_messages = trace_span!(target: THREAD_TRACE_MESSAGE_LOOP, "process_messages").entered();
         'process_messages: loop {
@@ -123,7 +151,7 @@ pub fn run() -> ThreadReturn {
                         debug!(target: THREAD_DEBUG_MESSAGE_RECEIVED, ?program_message, "got program message");
                         match program_message {
                             QuitAppNoError(QuitInteractionByUser) => {
-                                handle_user_quit(msg_sender, msg_receiver, threads)?;
+                                handle_user_quit(msg_sender, msg_receiver, threads, auto_save_config_path().as_deref())?;
                                 break 'global;
                             }
                             QuitAppError(wrapped_error_report) => return Err(handle_error_quit(wrapped_error_report)),
@@ -145,6 +173,8 @@ pub fn run() -> ThreadReturn {
         */
         threads = check_threads_are_running(threads).wrap_err("failed thread status check")?;
 
+        // Read every time, so changes in the config UI apply straight away
+        let poll_interval = Duration::from_millis(read_config_value(|config| config.runtime.messaging.thread_poll_interval_ms));
         trace!(target: PROGRAM_TRACE_GLOBAL_LOOP, ?poll_interval, "sleeping");
         thread::sleep(poll_interval);
         span_global_loop_inner.exit();
@@ -237,7 +267,8 @@ fn handle_error_quit(wrapped_error_report: Arc<Report>) -> Report {
     }
 }
 
-fn handle_user_quit(message_sender: BroadcastSender<ThreadMessage>, message_receiver: BroadcastReceiver<ThreadMessage>, threads: Threads) -> FallibleFn {
+/// Tells the threads to quit and joins them, saving the config to `auto_save_path` (if there is one) once the UI thread has finished
+fn handle_user_quit(message_sender: BroadcastSender<ThreadMessage>, message_receiver: BroadcastReceiver<ThreadMessage>, threads: Threads, auto_save_path: Option<&Path>) -> FallibleFn {
     info!(target: PROGRAM_INFO_LIFECYCLE, "user wants to quit");
 
     // We have to unsubscribe from out receiver or it blocks the other threads because we haven't received the [ExitXXXThread] messages
@@ -245,105 +276,31 @@ fn handle_user_quit(message_sender: BroadcastSender<ThreadMessage>, message_rece
     message_receiver.unsubscribe();
     trace!(target: THREAD_DEBUG_MESSENGER_LIFETIME, "unsubscribed (program) message receiver");
     debug_span!(target: THREAD_DEBUG_GENERAL, "join_threads_and_quit").in_scope(|| {
-        debug_span!(target: THREAD_DEBUG_GENERAL, "stop_ui").in_scope(|| {
-            let message = Ui(UiThreadMessage::ExitUiThread);
-            debug!(target: THREAD_DEBUG_MESSAGE_SEND, ?message);
-            match message_sender.try_send(message) {
-                Ok(()) => {
-                    debug!(target: THREAD_DEBUG_GENERAL, "ui thread signalled, joining threads");
-                    let join_result = threads.ui.join();
-                    debug!(target: THREAD_DEBUG_GENERAL, ?join_result, "ui thread joined");
-                    match join_result {
-                        // Thread joined normally, [thread_return_value] is what the thread returned
-                        Ok(thread_return_value) => {
-                            match thread_return_value {
-                                Ok(return_value) => {
-                                    debug!(target: THREAD_DEBUG_GENERAL, ?return_value, "ui thread completed successfully");
-                                }
-                                Err(error) => {
-                                    // The ui thread failed while shutting down here
-                                    // If it failed normally then it would have been caught outside the 'process_messages loop
-                                    let error = error
-                                        .wrap_err("ui thread failed while shutting down")
-                                        .note("it is unlikely that the thread failed during normal execution, as that should have been caught earlier");
-                                    debug!(target: THREAD_DEBUG_GENERAL, ?error);
-                                    //TODO: Test how this error code works and is logged
-                                    return Err(error);
-                                }
-                            }
-                        }
-                        // Thread panicked while quitting
-                        Err(boxed_panic) => {
-                            // Unfortunately we can't use the error for a report since it doesn't implement Sync, and it's dyn
-                            // So we have to format it as a string
-                            let report = dyn_panic_to_report(&boxed_panic)
-                                .wrap_err("ui thread panicked while shutting down")
-                                .note("it is unlikely that the thread failed during normal execution, as that should have been caught earlier");
-                            debug!(target: THREAD_DEBUG_GENERAL, ?boxed_panic, ?report);
-                            return Err(report);
-                        }
+        // Signal both threads before joining either, so they shut down at the same time instead of one after the other
+        debug_span!(target: THREAD_DEBUG_GENERAL, "signal_threads").in_scope(|| {
+            for message in [Ui(UiThreadMessage::ExitUiThread), Engine(EngineThreadMessage::ExitEngineThread)] {
+                debug!(target: THREAD_DEBUG_MESSAGE_SEND, ?message);
+                match message_sender.try_send(message) {
+                    Ok(()) => debug!(target: THREAD_DEBUG_GENERAL, "thread signalled"),
+                    // Neither of these errors should happen ever, but better to be safe
+                    Err(Disconnected(_failed_message)) => {
+                        return Err(error_send_never_should_be_disconnected().note(format!("attempted to send quit signal: {_failed_message:?}")));
                     }
-                }
-
-                // Neither of these errors should happen ever, but better to be safe
-                Err(Disconnected(_failed_message)) => {
-                    return Err(error_recv_never_should_be_disconnected().note(format!("attempted to send quit signal to ui thread: {_failed_message:?}")));
-                }
-                Err(Full(_failed_message)) => {
-                    return Err(error_recv_never_should_be_disconnected().note(format!("attempted to send quit signal to ui thread: {_failed_message:?}")));
-                }
-            }
-
-            Ok(())
-        })?; //end stop_ui
-
-        debug_span!(target: THREAD_DEBUG_GENERAL, "stop_engine").in_scope(|| {
-            match message_sender.try_send(Engine(EngineThreadMessage::ExitEngineThread)) {
-                Ok(()) => {
-                    debug!(target: THREAD_DEBUG_GENERAL, "engine thread signalled, joining threads");
-                    let join_result = threads.engine.join();
-                    debug!(target: THREAD_DEBUG_GENERAL, ?join_result, "engine thread joined");
-                    match join_result {
-                        // Thread joined normally, [thread_return_value] is what the thread returned
-                        Ok(thread_return_value) => {
-                            match thread_return_value {
-                                Ok(return_value) => {
-                                    debug!(target: THREAD_DEBUG_GENERAL, ?return_value, "engine thread completed successfully");
-                                }
-                                Err(error) => {
-                                    // The engine thread failed while shutting down here
-                                    // If it failed normally then it would have been caught outside the 'process_messages loop
-                                    let error = error
-                                        .wrap_err("engine thread failed while shutting down")
-                                        .note("it is unlikely that the thread failed during normal execution, as that should have been caught earlier");
-                                    debug!(target: THREAD_DEBUG_GENERAL, ?error);
-                                    //TODO: Test how this error code works and is logged
-                                    return Err(error);
-                                }
-                            }
-                        }
-                        // Thread panicked while quitting
-                        Err(boxed_panic) => {
-                            // Unfortunately we can't use the error for a report since it doesn't implement Sync, and it's dyn
-                            // So we have to format it as a string
-                            let report = dyn_panic_to_report(&boxed_panic).wrap_err("engine thread panicked while shutting down");
-                            debug!(target: THREAD_DEBUG_GENERAL, ?boxed_panic, ?report);
-                            return Err(report);
-                        }
+                    Err(Full(_failed_message)) => {
+                        return Err(error_never_should_be_full().note(format!("attempted to send quit signal: {_failed_message:?}")));
                     }
                 }
-
-                // Neither of these errors should happen ever, but better to be safe
-                Err(Disconnected(_failed_message)) => {
-                    return Err(error_recv_never_should_be_disconnected().note(format!("attempted to send quit signal to engine thread: {_failed_message:?}")));
-                }
-                Err(Full(_failed_message)) => {
-                    return Err(error_recv_never_should_be_disconnected().note(format!("attempted to send quit signal to engine thread: {_failed_message:?}")));
-                }
             }
-
             Ok(())
-        })?; //end stop_engine
+        })?; //end signal_threads
+
+        let ui_join_result = debug_span!(target: THREAD_DEBUG_GENERAL, "join_ui").in_scope(|| join_thread_on_quit("ui", threads.ui));
+        // Saved once the UI thread has finished, since it writes things like the open windows into the config at the end of each frame
+        // The quit message is sent while building the frame, so if we saved any earlier we could miss the last frame's changes
+        // The UI thread failing to shut down cleanly doesn't mean the config is bad, so it's always saved before we return that error
+        auto_save_config(auto_save_path);
+        ui_join_result?;
+        debug_span!(target: THREAD_DEBUG_GENERAL, "join_engine").in_scope(|| join_thread_on_quit("engine", threads.engine))?;
 
         // We know all is well if we get here, since we return immediately on any error when joining
         debug!(target: THREAD_DEBUG_GENERAL, "engine and ui threads joined successfully");
@@ -353,3 +310,154 @@ fn handle_user_quit(message_sender: BroadcastSender<ThreadMessage>, message_rece
 
     Ok(())
 }
+
+/// Where the config should be saved to as the app exits, or [None] if it shouldn't be
+///
+/// It isn't saved if [auto_save_config_on_exit](crate::config::run_time::RuntimeAppConfig::auto_save_config_on_exit) is disabled,
+/// or if the config file couldn't be loaded at startup, since we'd be overwriting the user's config with the defaults
+fn auto_save_config_path() -> Option<PathBuf> {
+    if !read_config_value(|config| config.runtime.auto_save_config_on_exit) {
+        debug!(target: PROGRAM_DEBUG_GENERAL, "config auto-save disabled, not saving config");
+        return None;
+    }
+    if config_load_failed() {
+        warn!(target: GENERAL_WARNING_NON_FATAL, "config file couldn't be loaded at startup, not auto-saving so it isn't overwritten with the defaults");
+        return None;
+    }
+    match config_file_path() {
+        Ok(path) => Some(path),
+        Err(report) => {
+            warn!(target: GENERAL_WARNING_NON_FATAL, report = %format_report_display(&report), "couldn't get config file path, changes since the last save are lost");
+            None
+        }
+    }
+}
+
+/// Saves the config to `path`, if there is one (see [auto_save_config_path])
+///
+/// Failing to save isn't a reason to stop quitting, so errors are only logged
+fn auto_save_config(path: Option<&Path>) {
+    let path = match path {
+        Some(path) => path,
+        None => return,
+    };
+    debug!(target: PROGRAM_DEBUG_GENERAL, ?path, "auto-saving config to disk");
+    if let Err(report) = save_config_to(path) {
+        warn!(target: GENERAL_WARNING_NON_FATAL, report = %format_report_display(&report), "couldn't save config to disk, changes since the last save are lost");
+    }
+}
+
+/// Joins a thread that has been told to quit, turning it failing or panicking while shutting down into an error
+fn join_thread_on_quit(thread_name: &str, handle: ThreadHandle) -> FallibleFn {
+    debug!(target: THREAD_DEBUG_GENERAL, thread_name, "joining thread");
+    let join_result = handle.join();
+    debug!(target: THREAD_DEBUG_GENERAL, thread_name, ?join_result, "thread joined");
+    match join_result {
+        // Thread joined normally, [thread_return_value] is what the thread returned
+        Ok(Ok(return_value)) => {
+            debug!(target: THREAD_DEBUG_GENERAL, thread_name, ?return_value, "thread completed successfully");
+            Ok(())
+        }
+        // The thread failed while shutting down here
+        // If it failed normally then it would have been caught outside the 'process_messages loop
+        Ok(Err(error)) => {
+            let error = error
+                .wrap_err(format!("{thread_name} thread failed while shutting down"))
+                .note("it is unlikely that the thread failed during normal execution, as that should have been caught earlier");
+            debug!(target: THREAD_DEBUG_GENERAL, ?error);
+            //TODO: Test how this error code works and is logged
+            Err(error)
+        }
+        // Thread panicked while quitting
+        Err(boxed_panic) => {
+            // Unfortunately we can't use the error for a report since it doesn't implement Sync, and it's dyn
+            // So we have to format it as a string
+            let report = dyn_panic_to_report(&boxed_panic)
+                .wrap_err(format!("{thread_name} thread panicked while shutting down"))
+                .note("it is unlikely that the thread failed during normal execution, as that should have been caught earlier");
+            debug!(target: THREAD_DEBUG_GENERAL, ?boxed_panic, ?report);
+            Err(report)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::ops::Range;
+    use std::time::Instant;
+
+    /// When a fake thread started shutting down (got its exit message), until when it finished
+    type ShutdownTimes = Arc<Mutex<Vec<Range<Instant>>>>;
+
+    /// Spawns a thread that stands in for the UI or engine thread, and just waits until it gets the message telling it to exit
+    ///
+    /// It then takes `shutdown_time` to actually exit, like a real thread finishing off its last frame or iteration, and records when it did so in `shutdown_times`
+    fn spawn_fake_thread(receiver: BroadcastReceiver<ThreadMessage>, is_exit_message: fn(&ThreadMessage) -> bool, shutdown_time: Duration, shutdown_times: ShutdownTimes) -> ThreadHandle {
+        thread::spawn(move || {
+            while !is_exit_message(&wait_for_message(&receiver)?) {}
+            let shutdown_start = Instant::now();
+            receiver.unsubscribe();
+            thread::sleep(shutdown_time);
+            shutdown_times.lock().push(shutdown_start..Instant::now());
+            Ok(())
+        })
+    }
+
+    fn spawn_fake_threads(receiver: &BroadcastReceiver<ThreadMessage>, shutdown_time: Duration) -> (Threads, ShutdownTimes) {
+        let shutdown_times = ShutdownTimes::default();
+        let threads = Threads {
+            ui: spawn_fake_thread(
+                receiver.add_stream(),
+                |message| matches!(message, Ui(UiThreadMessage::ExitUiThread)),
+                shutdown_time,
+                Arc::clone(&shutdown_times),
+            ),
+            engine: spawn_fake_thread(
+                receiver.add_stream(),
+                |message| matches!(message, Engine(EngineThreadMessage::ExitEngineThread)),
+                shutdown_time,
+                Arc::clone(&shutdown_times),
+            ),
+        };
+        (threads, shutdown_times)
+    }
+
+    #[test]
+    fn clean_exit_saves_config() -> FallibleFn {
+        // Save somewhere else, so we don't overwrite the real config
+        let path = std::env::temp_dir().join(format!("rust_ray_clean_exit_saves_config_{}.ron", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let (sender, receiver) = broadcast_queue::<ThreadMessage>(100);
+        let (threads, _) = spawn_fake_threads(&receiver, Duration::ZERO);
+        handle_user_quit(sender, receiver, threads, Some(&path))?;
+
+        let saved = fs::read_to_string(&path)?;
+        fs::remove_file(&path)?;
+        let saved: AppConfig = ron::from_str(&saved)?;
+        assert_eq!(saved, read_config_value(|config| config.clone()));
+        Ok(())
+    }
+
+    #[test]
+    fn threads_shut_down_at_the_same_time() -> FallibleFn {
+        // Long enough that both threads should have been signalled well before either finishes
+        const SHUTDOWN_TIME: Duration = Duration::from_millis(200);
+        let (sender, receiver) = broadcast_queue::<ThreadMessage>(100);
+        let (threads, shutdown_times) = spawn_fake_threads(&receiver, SHUTDOWN_TIME);
+
+        // Don't auto-save, this isn't testing that
+        handle_user_quit(sender, receiver, threads, None)?;
+
+        // If the threads were signalled one after the other, the second one couldn't start shutting down until the first had finished
+        let shutdown_times = shutdown_times.lock();
+        assert_eq!(shutdown_times.len(), 2);
+        let (first, second) = (&shutdown_times[0], &shutdown_times[1]);
+        assert!(first.start < second.end && second.start < first.end, "threads didn't shut down at the same time: {shutdown_times:?}");
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 //! Internal module that contains implementations of enums for messages that can be sent upstream by the engine and UI threads to the main thread
 
-use std::sync::mpsc::TryRecvError;
+use std::sync::mpsc::{RecvError, TryRecvError};
 use std::sync::mpsc::TrySendError::{Disconnected, Full};
 use std::sync::Arc;
 
@@ -58,6 +58,27 @@ pub(crate) enum UiThreadMessage {
 pub(crate) enum EngineThreadMessage {
     /// The engine thread should exit
     ExitEngineThread,
+    /// The engine loop should stop doing anything until it gets [Resume](EngineThreadMessage::Resume). Messages are still processed while paused
+    Pause,
+    /// The engine loop should carry on from where it was [paused](EngineThreadMessage::Pause)
+    Resume,
+    /// The engine loop should run exactly once more, then stay paused. Ignored if the engine isn't paused
+    Step,
+}
+
+// ========== ERRORS ==========
+
+/// Errors that can happen when sending or receiving [ThreadMessage]s
+///
+/// These are wrapped in a [Report] by the helper functions below, so callers can use [Report::downcast_ref] to check which one happened
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub(crate) enum MessageError {
+    /// All the senders (when receiving) or receivers (when sending) on the other end of the channel were dropped
+    #[error("channel disconnected")]
+    Disconnected,
+    /// The channel's buffer was full, so the message couldn't be sent
+    #[error("channel full")]
+    Full,
 }
 
 // ========== MACROS AND FUNCTIONS ==========
@@ -87,15 +108,13 @@ impl ThreadMessage {
 ///
 /// So (in working code) we should never get here
 pub(crate) fn error_recv_never_should_be_disconnected() -> Report {
-    Report::msg(indoc::formatdoc! {
-        "all message channel senders were dropped: [try_recv()] returned [{0:?}] \"{0}\"",
-        ::std::sync::mpsc::TryRecvError::Disconnected
-    })
-    .wrap_err("critical invalid state")
-    .note(indoc::formatdoc! {r"
-            ui/engine senders should only be dropped when exiting threads, and program sender should never be dropped.
-            something probably went (badly) wrong somewhere else
-    "})
+    Report::new(MessageError::Disconnected)
+        .wrap_err("all message channel senders were dropped: [try_recv()] returned [Disconnected]")
+        .wrap_err("critical invalid state")
+        .note(indoc::formatdoc! {r"
+                ui/engine senders should only be dropped when exiting threads, and program sender should never be dropped.
+                something probably went (badly) wrong somewhere else
+        "})
 }
 /// Function that contains shared code for the case when [multiqueue2::broadcast::BroadcastReceiver::try_send] returns [std::sync::mpsc::TrySendError::Disconnected] in any of the message loops
 ///
@@ -108,15 +127,13 @@ pub(crate) fn error_recv_never_should_be_disconnected() -> Report {
 ///
 /// So (in working code) we should never get here
 pub(crate) fn error_send_never_should_be_disconnected() -> Report {
-    Report::msg(indoc::formatdoc! {
-        "all message channel senders were dropped: [try_recv()] returned [{0:?}] \"{0}\"",
-        ::std::sync::mpsc::TryRecvError::Disconnected
-    })
-    .wrap_err("critical invalid state")
-    .note(indoc::formatdoc! {r"
-            ui/engine senders should only be dropped when exiting threads, and program sender should never be dropped.
-            something probably went (badly) wrong somewhere else
-    "})
+    Report::new(MessageError::Disconnected)
+        .wrap_err("all message channel receivers were dropped: [try_send()] returned [Disconnected]")
+        .wrap_err("critical invalid state")
+        .note(indoc::formatdoc! {r"
+                ui/engine receivers should only be dropped when exiting threads, and program receiver should never be dropped until shutdown.
+                something probably went (badly) wrong somewhere else
+        "})
 }
 
 /// Function that contains shared code for the case when [multiqueue2::broadcast::BroadcastReceiver::try_send] returns [std::sync::mpsc::TrySendError::Full] in any of the message loops
@@ -129,15 +146,13 @@ pub(crate) fn error_send_never_should_be_disconnected() -> Report {
 ///
 /// Either way, shouldn't be possible, very bad
 pub(crate) fn error_never_should_be_full() -> Report {
-    Report::msg(indoc::formatdoc! {
-        "message channel was full: [try_send()] returned [{0:?}] \"{0}\"",
-        ::std::sync::mpsc::TrySendError::Full(0) //argument is arbitrary
-    })
-    .wrap_err("critical invalid state")
-    .note(indoc::formatdoc! {r"
-        message buffer should never be full - it has a high capacity and receivers should constantly be polling.
-        most likely, one of the other threads crashed or deadlocked (and therefore can't receive)
-    "})
+    Report::new(MessageError::Full)
+        .wrap_err("message channel was full: [try_send()] returned [Full]")
+        .wrap_err("critical invalid state")
+        .note(indoc::formatdoc! {r"
+            message buffer should never be full - it has a high capacity and receivers should constantly be polling.
+            most likely, one of the other threads crashed or deadlocked (and therefore can't receive)
+        "})
 }
 
 /// Receives a [ThreadMessage] from a [BroadcastReceiver]
@@ -163,6 +178,20 @@ pub(crate) fn receive_message(receiver: &BroadcastReceiver<ThreadMessage>) -> ey
     }
 }
 
+/// Like [receive_message], but waits until there's a message instead of returning `Ok(None)`
+///
+/// The queue parks the thread on a condvar while it waits, so this doesn't spin
+pub(crate) fn wait_for_message(receiver: &BroadcastReceiver<ThreadMessage>) -> eyre::Result<ThreadMessage> {
+    trace!(target: THREAD_TRACE_MESSAGE_LOOP, "message_receiver.recv()");
+    match receiver.recv() {
+        Err(RecvError) => Err(error_recv_never_should_be_disconnected()),
+        Ok(message) => {
+            trace!(target: THREAD_TRACE_MESSAGE_LOOP, ?message, "got message");
+            Ok(message)
+        }
+    }
+}
+
 pub(crate) fn send_message(message: ThreadMessage, sender: &BroadcastSender<ThreadMessage>) -> FallibleFn {
     debug!(target: THREAD_DEBUG_MESSAGE_SEND, ?message);
     match sender.try_send(message) {
@@ -170,7 +199,7 @@ pub(crate) fn send_message(message: ThreadMessage, sender: &BroadcastSender<Thre
 
         // Neither of these errors should happen ever, but better to be safe
         Err(Disconnected(_failed_message)) => {
-            return Err(error_recv_never_should_be_disconnected().section(format!("{_failed_message:?}").header("Message")));
+            return Err(error_send_never_should_be_disconnected().section(format!("{_failed_message:?}").header("Message")));
         }
         Err(Full(_failed_message)) => {
             return Err(error_never_should_be_full().section(format!("{_failed_message:?}").header("Message")));
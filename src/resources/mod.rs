@@ -1 +1,3 @@
 pub mod resource_manager;
+pub mod resource_watcher;
+pub mod theme_presets;
@@ -1,10 +1,91 @@
+use std::env;
 use std::path::PathBuf;
 
 use crate::config::read_config_value;
-use color_eyre::eyre;
+use crate::config::run_time::resources_config::ResourcesConfig;
+use color_eyre::eyre::WrapErr;
+use color_eyre::section::SectionExt;
+use color_eyre::{eyre, Help, Report};
+use tracing::{debug, trace};
 
 use crate::helper::file_helper::app_current_directory;
+use crate::helper::logging::event_targets::*;
 
+/// The different kinds of resources the app can load, each of which lives in its own subfolder of the main resources folder
+///
+/// The subfolder for each kind comes from the [ResourcesConfig], so they can be moved around without recompiling
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ResourceKind {
+    Fonts,
+    Output,
+    Themes,
+    Scenes,
+    Textures,
+    Meshes,
+    EnvironmentMaps,
+    Shaders,
+    CrashReports,
+    Screenshots,
+}
+
+impl ResourceKind {
+    /// Gets the subfolder (relative to the main resources folder) that resources of this kind are stored in
+    pub fn subfolder(self, config: &ResourcesConfig) -> &str {
+        match self {
+            ResourceKind::Fonts => &config.fonts_path,
+            ResourceKind::Output => &config.output_path,
+            ResourceKind::Themes => &config.themes_path,
+            ResourceKind::Scenes => &config.scenes_path,
+            ResourceKind::Textures => &config.textures_path,
+            ResourceKind::Meshes => &config.meshes_path,
+            ResourceKind::EnvironmentMaps => &config.environment_maps_path,
+            ResourceKind::Shaders => &config.shaders_path,
+            ResourceKind::CrashReports => &config.crash_reports_path,
+            ResourceKind::Screenshots => &config.screenshots_path,
+        }
+    }
+}
+
+/// Environment variable that can be set to override where the resources folder is
+pub const RESOURCES_ENV_VAR: &str = "RUST_RAY_RESOURCES";
+
+/// Finds the main resources folder
+///
+/// Checks these locations in order, returning the first one that exists:
+/// 1. The path in the [RESOURCES_ENV_VAR] environment variable
+/// 2. The [resources_path](crate::config::run_time::resources_config::ResourcesConfig::resources_path) in the executable's directory
+/// 3. The [resources_path](crate::config::run_time::resources_config::ResourcesConfig::resources_path) in the current working directory
+///
+/// This means the app still finds its resources when the binary is moved, or run from somewhere other than the project root
 pub fn get_main_resource_folder_path() -> eyre::Result<PathBuf> {
-    Ok(app_current_directory()?.join(read_config_value(|config| config.runtime.resources.resources_path.clone())))
+    let resources_path = read_config_value(|config| config.runtime.resources.resources_path.clone());
+
+    let mut candidates: Vec<(&str, PathBuf)> = vec![];
+    if let Some(env_path) = env::var_os(RESOURCES_ENV_VAR) {
+        candidates.push(("environment variable", PathBuf::from(env_path)));
+    }
+    match app_current_directory() {
+        Ok(exe_dir) => candidates.push(("executable directory", exe_dir.join(&resources_path))),
+        Err(report) => trace!(target: RESOURCES_DEBUG_LOAD, ?report, "could not get executable directory, skipping"),
+    }
+    let current_dir = env::current_dir().wrap_err("could not get current working directory")?;
+    candidates.push(("working directory", current_dir.join(&resources_path)));
+
+    for (source, path) in &candidates {
+        if path.is_dir() {
+            debug!(target: RESOURCES_DEBUG_LOAD, source, ?path, "found resources folder");
+            return Ok(path.clone());
+        }
+        trace!(target: RESOURCES_DEBUG_LOAD, source, ?path, "resources folder does not exist here");
+    }
+
+    Err(Report::msg("could not find the resources folder"))
+        .with_section(|| candidates.iter().map(|(source, path)| format!("{source}: {}", path.display())).collect::<Vec<_>>().join("\n").header("locations checked:"))
+        .with_suggestion(|| format!("set the `{RESOURCES_ENV_VAR}` environment variable to the path of the resources folder"))
+}
+
+/// Gets the path of the folder that resources of the given kind are stored in. The folder isn't guaranteed to exist
+pub fn resource_folder_path(kind: ResourceKind) -> eyre::Result<PathBuf> {
+    let subfolder = read_config_value(|config| kind.subfolder(&config.runtime.resources).to_owned());
+    Ok(get_main_resource_folder_path()?.join(subfolder))
 }
@@ -0,0 +1,92 @@
+//! Watches resource folders for changes, so that resources can be hot-reloaded
+//!
+//! This polls the modification times of the files instead of using OS file notifications, which is simpler and plenty fast enough for the small resource folders we have.
+//! It's polled from the UI thread, so there's no separate thread to manage
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime};
+
+use fs_extra::dir;
+use tracing::{debug, trace};
+
+use crate::config::compile_time::resources_config::{RESOURCE_WATCH_DEBOUNCE, RESOURCE_WATCH_INTERVAL};
+use crate::config::read_config_value;
+use crate::helper::logging::event_targets::*;
+use crate::resources::resource_manager::{resource_folder_path, ResourceKind};
+
+/// The state of all the files in a resource folder: their paths, along with when they were last modified and their size
+type FolderSnapshot = HashMap<PathBuf, (Option<SystemTime>, u64)>;
+
+#[derive(Debug, Clone)]
+pub struct ResourceWatcher {
+    /// The snapshot of each watched folder from the last time it was checked
+    snapshots: HashMap<ResourceKind, FolderSnapshot>,
+    /// Folders that have changed, along with when they last changed. They get reloaded once they've settled down (see [RESOURCE_WATCH_DEBOUNCE])
+    pending: HashMap<ResourceKind, Instant>,
+    /// When the folders were last checked
+    last_poll: Option<Instant>,
+}
+
+impl ResourceWatcher {
+    /// Creates a new watcher for the given kinds of resources
+    pub fn new(kinds: &[ResourceKind]) -> Self {
+        Self {
+            snapshots: kinds.iter().map(|&kind| (kind, snapshot_folder(kind))).collect(),
+            pending: HashMap::new(),
+            last_poll: None,
+        }
+    }
+
+    /// Checks the watched folders for changes, returning the kinds of resources that should be reloaded
+    ///
+    /// Does nothing (and returns nothing) if hot-reloading is disabled in the config, or if it hasn't been long enough since the last check
+    pub fn poll(&mut self) -> Vec<ResourceKind> {
+        if !read_config_value(|config| config.runtime.resources.hot_reload) {
+            return vec![];
+        }
+        let now = Instant::now();
+        if self.last_poll.map_or(false, |last_poll| now - last_poll < RESOURCE_WATCH_INTERVAL) {
+            return vec![];
+        }
+        self.last_poll = Some(now);
+
+        for (kind, snapshot) in self.snapshots.iter_mut() {
+            let new_snapshot = snapshot_folder(*kind);
+            if new_snapshot != *snapshot {
+                trace!(target: RESOURCES_DEBUG_LOAD, ?kind, "resource folder changed");
+                *snapshot = new_snapshot;
+                self.pending.insert(*kind, now);
+            }
+        }
+
+        let settled: Vec<ResourceKind> = self.pending.iter().filter(|(_, &changed)| now - changed >= RESOURCE_WATCH_DEBOUNCE).map(|(&kind, _)| kind).collect();
+        for kind in &settled {
+            debug!(target: RESOURCES_DEBUG_LOAD, ?kind, "resource folder changed, should be reloaded");
+            self.pending.remove(kind);
+        }
+        settled
+    }
+}
+
+/// Takes a snapshot of the files in the folder for the given kind of resource. If the folder can't be read, the snapshot is empty
+fn snapshot_folder(kind: ResourceKind) -> FolderSnapshot {
+    let content = match resource_folder_path(kind).and_then(|path| Ok(dir::get_dir_content(path)?)) {
+        Ok(content) => content,
+        Err(report) => {
+            trace!(target: RESOURCES_DEBUG_LOAD, ?kind, ?report, "could not read resource folder for snapshot");
+            return HashMap::new();
+        }
+    };
+    content
+        .files
+        .into_iter()
+        .map(|file| {
+            let metadata = fs::metadata(&file).ok();
+            let modified = metadata.as_ref().and_then(|metadata| metadata.modified().ok());
+            let size = metadata.map_or(0, |metadata| metadata.len());
+            (PathBuf::from(file), (modified, size))
+        })
+        .collect()
+}
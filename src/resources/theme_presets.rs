@@ -0,0 +1,96 @@
+//! Saving and loading named [Theme] presets, which are stored as RON files in the themes folder (see [ResourcesConfig::themes_path](crate::config::run_time::resources_config::ResourcesConfig::themes_path))
+
+use std::fs;
+use std::path::PathBuf;
+
+use color_eyre::eyre::WrapErr;
+use color_eyre::{eyre, Help, Report};
+use ron::ser::{to_string_pretty, PrettyConfig};
+use tracing::{debug, trace, warn};
+
+use crate::config::run_time::ui_config::theme::Theme;
+use crate::helper::logging::event_targets::*;
+use crate::helper::logging::format_report_display;
+use crate::resources::resource_manager::{resource_folder_path, ResourceKind};
+use crate::FallibleFn;
+
+/// File extension used for theme preset files
+const THEME_PRESET_EXTENSION: &str = "ron";
+
+/// Gets the path of the folder that the theme presets are stored in
+pub fn get_themes_folder_path() -> eyre::Result<PathBuf> {
+    resource_folder_path(ResourceKind::Themes)
+}
+
+/// Loads all the theme presets in the themes folder, sorted by name
+///
+/// Files that can't be read or parsed are skipped (with a warning), so one bad file doesn't stop the rest from loading.
+/// If the themes folder doesn't exist, there are just no presets
+pub fn load_theme_presets() -> eyre::Result<Vec<(String, Theme)>> {
+    let themes_folder = get_themes_folder_path()?;
+    if !themes_folder.is_dir() {
+        trace!(target: RESOURCES_DEBUG_LOAD, ?themes_folder, "themes folder doesn't exist, no presets to load");
+        return Ok(vec![]);
+    }
+
+    let mut presets = vec![];
+    let entries = fs::read_dir(&themes_folder)
+        .wrap_err("could not read themes folder")
+        .with_note(|| format!("themes folder: {}", themes_folder.display()))?;
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(error) => {
+                warn!(target: RESOURCES_WARNING_NON_FATAL, ?error, "could not read entry in themes folder");
+                continue;
+            }
+        };
+        if path.extension().map_or(true, |extension| extension != THEME_PRESET_EXTENSION) {
+            trace!(target: RESOURCES_DEBUG_LOAD, ?path, "skipping non-theme file");
+            continue;
+        }
+        let name = match path.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => continue,
+        };
+
+        let theme = fs::read_to_string(&path)
+            .wrap_err("could not read theme preset file")
+            .and_then(|data| ron::from_str::<Theme>(&data).wrap_err("could not deserialise theme preset"));
+        match theme {
+            Ok(theme) => presets.push((name, theme)),
+            Err(report) => {
+                let report = report.note(format!("file: {}", path.display()));
+                warn!(target: RESOURCES_WARNING_NON_FATAL, report = format_report_display(&report), "skipping invalid theme preset");
+            }
+        }
+    }
+
+    presets.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    Ok(presets)
+}
+
+/// Saves a theme as a preset with the given name, overwriting any preset that already has that name
+pub fn save_theme_preset(name: &str, theme: &Theme) -> FallibleFn {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Report::msg("theme preset name was empty")).suggestion("enter a name for the preset");
+    }
+    if name.contains(['/', '\\', '.']) {
+        return Err(Report::msg(format!("theme preset name `{name}` was invalid"))).note("preset names can't contain slashes or dots, since they're used as file names");
+    }
+
+    let themes_folder = get_themes_folder_path()?;
+    fs::create_dir_all(&themes_folder)
+        .wrap_err("could not create themes folder")
+        .with_note(|| format!("themes folder: {}", themes_folder.display()))?;
+
+    let path = themes_folder.join(format!("{name}.{THEME_PRESET_EXTENSION}"));
+    let serialised = to_string_pretty(theme, PrettyConfig::default()).wrap_err("couldn't serialise theme")?;
+    fs::write(&path, serialised)
+        .wrap_err("couldn't save serialised theme to file")
+        .with_note(|| format!("file: {}", path.display()))?;
+
+    debug!(target: RESOURCES_DEBUG_LOAD, name, ?path, "saved theme preset");
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! The "About" dialog, showing the build information for the app
+
+use crate::build::{build_info, build_info_text, REPOSITORY_URL};
+use crate::config::read_config_value;
+use crate::helper::file_helper::open_url;
+use crate::helper::logging::event_targets::*;
+use crate::ui::build_ui_impl::shared::error_display::{an_error_occurred, clipboard_button};
+use crate::ui::build_ui_impl::shared::explain_mode::explain_item;
+use color_eyre::eyre::WrapErr;
+use imgui::{TableFlags, Ui};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use tracing::{debug, trace, trace_span};
+
+/// Set when the popup should be opened, since the menu item is inside the menu bar's ID stack and can't open it directly
+static SHOW_ABOUT_POPUP: AtomicBool = AtomicBool::new(false);
+
+const MODAL_NAME: &str = "About";
+
+/// Requests that the about popup be opened next frame
+pub(super) fn show_about_popup() {
+    SHOW_ABOUT_POPUP.store(true, Relaxed);
+}
+
+pub(super) fn render_about_popup(ui: &Ui) {
+    if SHOW_ABOUT_POPUP.swap(false, Relaxed) {
+        trace!(target: UI_TRACE_BUILD_INTERFACE, "opening about popup");
+        ui.open_popup(MODAL_NAME);
+    }
+
+    let _span = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "about_modal").entered();
+    let mut opened = true;
+    let popup_token = match ui.modal_popup_config(MODAL_NAME).opened(&mut opened).always_auto_resize(true).begin_popup() {
+        None => {
+            trace!(target: UI_TRACE_BUILD_INTERFACE, "about modal not visible");
+            return;
+        }
+        Some(token) => token,
+    };
+    let colours = read_config_value(|config| config.runtime.ui.colours);
+
+    if let Some(table_token) = ui.begin_table_with_flags("build info", 2, TableFlags::SIZING_FIXED_FIT) {
+        for (label, value) in build_info() {
+            ui.table_next_column();
+            ui.text_colored(colours.value.value_label, label);
+            ui.table_next_column();
+            ui.text_colored(colours.value.misc_value, value);
+        }
+        table_token.end();
+    }
+
+    ui.separator();
+    trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Copy");
+    if clipboard_button(ui, "Copy", "Copies the build information to the clipboard, for pasting into bug reports") {
+        ui.set_clipboard_text(build_info_text());
+    }
+    explain_item(
+        ui,
+        "Copies all the information above as text. Please include it when reporting a bug, so we know exactly which build you're running",
+    );
+    ui.same_line();
+    trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Open Repository");
+    if ui.button("Open Repository") {
+        debug!(target: UI_DEBUG_USER_INTERACTION, url = REPOSITORY_URL, "[Button] Open Repository pressed");
+        if let Err(report) = open_url(REPOSITORY_URL).wrap_err("could not open the repository in the browser") {
+            an_error_occurred(report);
+        }
+    }
+    if ui.is_item_hovered() {
+        ui.tooltip_text(REPOSITORY_URL);
+    }
+    explain_item(ui, "Opens the source code repository in your browser, where you can report bugs and see what's being worked on");
+
+    popup_token.end();
+}
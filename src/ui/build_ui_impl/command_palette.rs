@@ -0,0 +1,212 @@
+//! A searchable list of every action in the app (toggling windows, saving the config, quitting, etc.), opened with a keybinding
+//!
+//! Typing filters the actions with a fuzzy match, and pressing Enter runs the selected one
+use crate::config::run_time::keybindings_config::KeyCode;
+use crate::config::{load_config_from_disk, read_config_value, save_config_to_disk};
+use crate::helper::logging::event_targets::*;
+use crate::helper::logging::format_report_display;
+use crate::program::thread_messages::*;
+use crate::ui::build_ui_impl::quit_confirmation::request_quit;
+use crate::ui::build_ui_impl::shared::error_display::an_error_occurred;
+use crate::ui::build_ui_impl::shared::explain_mode::toggle_explain_mode;
+use crate::ui::build_ui_impl::shared::input::report_keybinding_conflicts;
+use crate::ui::build_ui_impl::window_registry::{window_title, WINDOWS};
+use crate::ui::ui_data::{UiData, WindowId};
+use crate::ui::ui_system::UiManagers;
+use crate::FallibleFn;
+use imgui::{Condition, Ui, WindowFlags};
+use multiqueue2::BroadcastSender;
+use parking_lot::{const_mutex, Mutex};
+use tracing::{debug, trace, trace_span, warn};
+
+/// State of the command palette. Only the UI thread uses this, the [Mutex] is just so it can be static
+static PALETTE: Mutex<PaletteState> = const_mutex(PaletteState {
+    open: false,
+    just_opened: false,
+    query: String::new(),
+    selected: 0,
+});
+
+struct PaletteState {
+    open: bool,
+    /// Set when the palette is opened, so the search box can be focused on the first frame
+    just_opened: bool,
+    /// What the user has typed into the search box
+    query: String,
+    /// Index (into the filtered actions) of the action that will be run when Enter is pressed
+    selected: usize,
+}
+
+/// Something the user can do from the command palette
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Action {
+    ToggleWindow(WindowId),
+    SaveConfig,
+    ReloadConfig,
+    ReloadFonts,
+    SaveScreenshot,
+    ResetLayout,
+    ToggleExplainMode,
+    Quit,
+}
+
+impl Action {
+    /// Every action, in the order they're listed when nothing has been typed
+    fn all() -> Vec<Action> {
+        let others = [
+            Action::SaveConfig,
+            Action::ReloadConfig,
+            Action::ReloadFonts,
+            Action::SaveScreenshot,
+            Action::ResetLayout,
+            Action::ToggleExplainMode,
+            Action::Quit,
+        ];
+        WINDOWS.iter().map(|window| Action::ToggleWindow(window.id)).chain(others).collect()
+    }
+
+    fn name(self) -> String {
+        match self {
+            Action::ToggleWindow(id) => format!("Toggle {}", window_title(id)),
+            Action::SaveConfig => "Save Config to Disk".to_owned(),
+            Action::ReloadConfig => "Reload Config from Disk".to_owned(),
+            Action::ReloadFonts => "Reload Fonts".to_owned(),
+            Action::SaveScreenshot => "Save Screenshot".to_owned(),
+            Action::ResetLayout => "Reset Window Layout".to_owned(),
+            Action::ToggleExplainMode => "Toggle Explain Mode".to_owned(),
+            Action::Quit => "Quit".to_owned(),
+        }
+    }
+}
+
+/// Opens the command palette (or closes it, if it's already open)
+pub(super) fn toggle_command_palette() {
+    let mut palette = PALETTE.lock();
+    palette.open ^= true;
+    palette.just_opened = palette.open;
+    palette.query.clear();
+    palette.selected = 0;
+    debug!(target: UI_DEBUG_USER_INTERACTION, open = palette.open, "toggled command palette");
+}
+
+/// Scores how well the `query` fuzzy-matches the `name`, or [None] if it doesn't match at all
+///
+/// Every character of the query has to appear in the name, in order (ignoring case). Matches with the characters closer together, and closer to the start, score higher
+fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name = name.to_lowercase();
+    let mut name_chars = name.chars().enumerate();
+    let mut score = 0;
+    let mut previous_index = None;
+    for query_char in query.to_lowercase().chars().filter(|char| !char.is_whitespace()) {
+        let (index, _) = name_chars.find(|&(_, name_char)| name_char == query_char)?;
+        // Consecutive characters are what the user most likely meant, gaps (and starting late) count against the match
+        let gap = match previous_index {
+            None => index,
+            Some(previous) => index - previous - 1,
+        };
+        score -= gap as i32;
+        previous_index = Some(index);
+    }
+    Some(score)
+}
+
+/// Renders the command palette (if it's open), and runs the chosen action
+pub(super) fn render_command_palette(ui: &Ui, data: &mut UiData, managers: &mut UiManagers, message_sender: &BroadcastSender<ThreadMessage>) -> FallibleFn {
+    let span_command_palette = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "command_palette").entered();
+    let mut palette = PALETTE.lock();
+    if !palette.open {
+        trace!(target: UI_TRACE_BUILD_INTERFACE, "command palette closed");
+        return Ok(());
+    }
+
+    let mut matches: Vec<(i32, Action)> = Action::all().into_iter().filter_map(|action| fuzzy_score(&palette.query, &action.name()).map(|score| (score, action))).collect();
+    // Stable, so actions that score the same stay in their usual order
+    matches.sort_by_key(|&(score, _)| -score);
+
+    if ui.is_key_index_pressed(KeyCode::Down as i32) && palette.selected + 1 < matches.len() {
+        palette.selected += 1;
+    }
+    if ui.is_key_index_pressed(KeyCode::Up as i32) {
+        palette.selected = palette.selected.saturating_sub(1);
+    }
+    palette.selected = palette.selected.min(matches.len().saturating_sub(1));
+    let mut chosen = None;
+
+    let colours = read_config_value(|config| config.runtime.ui.colours);
+    let [width, height] = ui.io().display_size;
+    ui.window("Command Palette")
+        .position([width * 0.5, height * 0.15], Condition::Always)
+        .position_pivot([0.5, 0.0])
+        .size([width * 0.4, 0.0], Condition::Always)
+        .flags(WindowFlags::NO_TITLE_BAR | WindowFlags::NO_MOVE | WindowFlags::NO_RESIZE | WindowFlags::NO_SAVED_SETTINGS | WindowFlags::NO_DOCKING)
+        .build(|| {
+            if palette.just_opened {
+                ui.set_keyboard_focus_here();
+                palette.just_opened = false;
+            }
+            let width_token = ui.push_item_width(-1.0);
+            let enter_pressed = ui.input_text("##command_palette_query", &mut palette.query).hint("Type a command...").enter_returns_true(true).build();
+            width_token.end();
+            if ui.is_item_edited() {
+                // The matches changed, so whatever was selected probably isn't there anymore
+                palette.selected = 0;
+            }
+            if enter_pressed {
+                chosen = matches.get(palette.selected).map(|&(_, action)| action);
+            }
+
+            if matches.is_empty() {
+                ui.text_colored(colours.value.missing_value, "No matching commands");
+            }
+            for (index, &(_, action)) in matches.iter().enumerate() {
+                if ui.selectable_config(action.name()).selected(index == palette.selected).build() {
+                    chosen = Some(action);
+                }
+            }
+        });
+
+    if ui.is_key_index_pressed_no_repeat(KeyCode::Escape as i32) {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "closed command palette");
+        palette.open = false;
+    }
+    let action = match chosen {
+        None => return Ok(()),
+        Some(action) => action,
+    };
+    palette.open = false;
+    // Running the action might need the palette (e.g. if it toggles it), so don't hold the lock
+    drop(palette);
+
+    debug!(target: UI_DEBUG_USER_INTERACTION, ?action, "running action from command palette");
+    match action {
+        Action::ToggleWindow(id) => *data.windows.open_mut(id) ^= true,
+        Action::SaveConfig => {
+            if let Err(report) = save_config_to_disk() {
+                warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report), "could not save config to disk");
+                an_error_occurred(report);
+            }
+        }
+        Action::ReloadConfig => match load_config_from_disk() {
+            Err(report) => {
+                warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report), "could not load config from disk");
+                an_error_occurred(report);
+            }
+            Ok(()) => report_keybinding_conflicts(&read_config_value(|config| config.runtime.keybindings)),
+        },
+        Action::ReloadFonts => {
+            if let Err(report) = managers.font_manager.reload_list_from_resources() {
+                an_error_occurred(report.wrap_err("could not reload fonts"));
+            }
+        }
+        Action::SaveScreenshot => managers.screenshot_requested = true,
+        Action::ResetLayout => managers.apply_default_layout = true,
+        Action::ToggleExplainMode => toggle_explain_mode(),
+        Action::Quit => {
+            debug!(target: UI_DEBUG_USER_INTERACTION, "user chose quit from command palette");
+            request_quit(message_sender)?;
+        }
+    }
+
+    span_command_palette.exit();
+    Ok(())
+}
@@ -1,20 +1,199 @@
-use crate::config::compile_time::ui_config::MAX_FRAMES_TO_TRACK;
+use crate::config::compile_time::ui_config::{MAX_CONFIG_UPDATE_ATTEMPTS, MAX_DISPLAYED_ERRORS, MAX_FONT_SIZE, MAX_FRAMES_TO_TRACK, MIN_FONT_SIZE};
+use crate::config::init_time::ui_config::{Backend, HiDpiSetting};
 use crate::config::init_time::InitTimeAppConfig;
+use crate::config::run_time::keybindings_config::KeyBinding;
+use crate::config::run_time::tracing_config::LogTargetFilter;
 use crate::config::run_time::ui_config::theme::{Colour, Theme};
 use crate::config::run_time::RuntimeAppConfig;
-use crate::config::{load_config_from_disk, read_config_value, save_config_to_disk, update_config};
+use crate::config::{load_config_from_disk, read_config_value, reapply_changes, save_config_to_disk, try_update_config, AppConfig};
 use crate::helper::logging::event_targets::*;
 use crate::helper::logging::format_report_display;
+use crate::resources::resource_manager::{resource_folder_path, ResourceKind};
+use crate::resources::theme_presets::{load_theme_presets, save_theme_preset};
 use crate::ui::build_ui_impl::shared::error_display::an_error_occurred;
+use crate::ui::build_ui_impl::shared::explain_mode::explain_item;
+use crate::ui::build_ui_impl::shared::input::{begin_keybinding_capture, keybinding_capture_target, poll_captured_keybinding, report_keybinding_conflicts};
 use crate::ui::build_ui_impl::UiItem;
+use crate::ui::ui_system::{Toast, UiManagers};
 use crate::FallibleFn;
 use color_eyre::Report;
-use imgui::{ColorPreview, SliderFlags, TreeNodeFlags, Ui};
+use imgui::{ColorPreview, Condition, SliderFlags, StyleColor, TableFlags, TreeNodeFlags, TreeNodeToken, Ui};
 use indoc::indoc;
+use lazy_static::lazy_static;
+use parking_lot::{const_mutex, Mutex};
 use tracing::{debug, trace, trace_span, warn};
 use vek::num_traits::real::Real;
 
-pub(super) fn render_config_ui(ui: &Ui, visible: bool) -> FallibleFn {
+/// Name that the user has typed in for saving the current theme as a preset
+static THEME_PRESET_NAME: Mutex<String> = const_mutex(String::new());
+
+/// What the user has typed into the config search box
+static CONFIG_SEARCH: Mutex<String> = const_mutex(String::new());
+
+lazy_static! {
+    /// The default config, to compare against so that values the user has changed can be highlighted
+    static ref DEFAULT_CONFIG: AppConfig = AppConfig::default();
+}
+
+/// How the config tree is displayed: which parts of it match what's typed into the search box, and how values that aren't the default are highlighted
+#[derive(Copy, Clone, Debug)]
+struct ConfigTree<'a> {
+    /// The search query, in lowercase
+    query: &'a str,
+    /// Set once a section's name has matched, so that everything inside it is shown
+    show_all: bool,
+    /// Colour for highlighting values that aren't the default
+    modified_colour: Colour,
+}
+
+impl<'a> ConfigTree<'a> {
+    fn new(query: &'a str) -> Self {
+        Self {
+            query,
+            show_all: false,
+            modified_colour: read_config_value(|config| config.runtime.ui.colours.text.accent),
+        }
+    }
+
+    fn is_searching(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    /// Whether the field with this label should be shown
+    fn matches(&self, label: &str) -> bool {
+        self.show_all || !self.is_searching() || label.to_lowercase().contains(self.query)
+    }
+
+    /// The tree to use for everything inside the section `name`
+    fn within(self, name: &str) -> Self {
+        Self {
+            show_all: self.show_all || (self.is_searching() && self.matches(name)),
+            ..self
+        }
+    }
+
+    /// Begins the tree node for a section, with a badge showing how many values inside it aren't the default.
+    /// While searching, sections with a match are forced open and sections without one are hidden
+    fn section<'ui>(&self, ui: &'ui Ui, name: &str, children_match: bool, modified: usize) -> Option<TreeNodeToken<'ui>> {
+        let node = if self.show_all || !self.is_searching() {
+            ui.tree_node(name)
+        } else if children_match || self.matches(name) {
+            // Different ID to the normal node, so that forcing it open doesn't change whether it's open once the search is cleared
+            ui.tree_node_config(format!("{name}##searching")).opened(true, Condition::Always).push()
+        } else {
+            return None;
+        };
+        if modified > 0 {
+            ui.same_line();
+            ui.text_colored(self.modified_colour, format!("({modified} modified)"));
+        }
+        node
+    }
+}
+
+/// Renders a section of the config tree, hiding the fields (and sections) that don't match the search, and highlighting values that aren't the default.
+/// `$original` is the config as it was before this frame's changes, and `$default` the default config, which field paths are looked up in
+///
+/// Each item in a section is one of:
+/// * `prelude { ... }`: statements that run when the section is open, before any of its fields
+/// * `"Label" [path.to.value] => { ... }`: a field for the value at `path.to.value`, with the label bound to the `$label` identifier inside the body
+/// * `"Label" => { ... }`: a field that isn't a value in the config (like a button), so is never highlighted
+/// * `separator;`: a separator between fields, which is skipped while searching
+/// * `section "Name" { ... }`: a nested section
+/// * `custom "Name" (labels) modified (count) |tree| { ... }`: a section that renders itself, that is shown if any of the `labels` match, and has `count` modified values
+macro_rules! config_section {
+    // Whether anything in a section matches the search
+    (@matches $tree:ident,) => { false };
+    (@matches $tree:ident, prelude $prelude:tt $($rest:tt)*) => { config_section!(@matches $tree, $($rest)*) };
+    (@matches $tree:ident, separator; $($rest:tt)*) => { config_section!(@matches $tree, $($rest)*) };
+    (@matches $tree:ident, section $name:literal { $($inner:tt)* } $($rest:tt)*) => {
+        $tree.matches($name) || config_section!(@matches $tree, $($inner)*) || config_section!(@matches $tree, $($rest)*)
+    };
+    (@matches $tree:ident, custom $name:literal ($labels:expr) modified ($count:expr) |$inner_tree:ident| $body:block $($rest:tt)*) => {
+        $tree.matches($name) || $labels.iter().any(|label| $tree.matches(label)) || config_section!(@matches $tree, $($rest)*)
+    };
+    (@matches $tree:ident, $field:literal [$($path:ident).+] => $body:block $($rest:tt)*) => { $tree.matches($field) || config_section!(@matches $tree, $($rest)*) };
+    (@matches $tree:ident, $field:literal => $body:block $($rest:tt)*) => { $tree.matches($field) || config_section!(@matches $tree, $($rest)*) };
+
+    // How many values in a section aren't the default
+    (@modified $original:ident, $default:ident,) => { 0usize };
+    (@modified $original:ident, $default:ident, prelude $prelude:tt $($rest:tt)*) => { config_section!(@modified $original, $default, $($rest)*) };
+    (@modified $original:ident, $default:ident, separator; $($rest:tt)*) => { config_section!(@modified $original, $default, $($rest)*) };
+    (@modified $original:ident, $default:ident, section $name:literal { $($inner:tt)* } $($rest:tt)*) => {
+        config_section!(@modified $original, $default, $($inner)*) + config_section!(@modified $original, $default, $($rest)*)
+    };
+    (@modified $original:ident, $default:ident, custom $name:literal ($labels:expr) modified ($count:expr) |$inner_tree:ident| $body:block $($rest:tt)*) => {
+        $count + config_section!(@modified $original, $default, $($rest)*)
+    };
+    (@modified $original:ident, $default:ident, $field:literal [$($path:ident).+] => $body:block $($rest:tt)*) => {
+        usize::from($original.$($path).+ != $default.$($path).+) + config_section!(@modified $original, $default, $($rest)*)
+    };
+    (@modified $original:ident, $default:ident, $field:literal => $body:block $($rest:tt)*) => { config_section!(@modified $original, $default, $($rest)*) };
+
+    // Rendering the items in a section
+    (@items [$ui:ident, $tree:ident, $label:ident, $original:ident, $default:ident]) => {};
+    (@items [$ui:ident, $tree:ident, $label:ident, $original:ident, $default:ident] prelude { $($prelude:tt)* } $($rest:tt)*) => {
+        $($prelude)*
+        config_section!(@items [$ui, $tree, $label, $original, $default] $($rest)*);
+    };
+    (@items [$ui:ident, $tree:ident, $label:ident, $original:ident, $default:ident] separator; $($rest:tt)*) => {
+        if !$tree.is_searching() {
+            $ui.separator();
+        }
+        config_section!(@items [$ui, $tree, $label, $original, $default] $($rest)*);
+    };
+    (@items [$ui:ident, $tree:ident, $label:ident, $original:ident, $default:ident] section $name:literal { $($inner:tt)* } $($rest:tt)*) => {
+        match $tree.section($ui, $name, config_section!(@matches $tree, $($inner)*), config_section!(@modified $original, $default, $($inner)*)) {
+            None => trace!(target: UI_TRACE_BUILD_INTERFACE, section = $name, "config section collapsed"),
+            Some(node) => {
+                let $tree = $tree.within($name);
+                {
+                    config_section!(@items [$ui, $tree, $label, $original, $default] $($inner)*);
+                }
+                node.end();
+            }
+        }
+        config_section!(@items [$ui, $tree, $label, $original, $default] $($rest)*);
+    };
+    (@items [$ui:ident, $tree:ident, $label:ident, $original:ident, $default:ident] custom $name:literal ($labels:expr) modified ($count:expr) |$inner_tree:ident| $body:block $($rest:tt)*) => {
+        match $tree.section($ui, $name, $labels.iter().any(|label| $tree.matches(label)), $count) {
+            None => trace!(target: UI_TRACE_BUILD_INTERFACE, section = $name, "config section collapsed"),
+            Some(node) => {
+                let $inner_tree = $tree.within($name);
+                $body
+                node.end();
+            }
+        }
+        config_section!(@items [$ui, $tree, $label, $original, $default] $($rest)*);
+    };
+    (@items [$ui:ident, $tree:ident, $label:ident, $original:ident, $default:ident] $field:literal [$($path:ident).+] => $body:block $($rest:tt)*) => {
+        if $tree.matches($field) {
+            let $label = $field;
+            let modified = $original.$($path).+ != $default.$($path).+;
+            {
+                let _modified_colour = modified.then(|| $ui.push_style_color(StyleColor::Text, $tree.modified_colour));
+                $body
+            }
+            if modified && $ui.is_item_hovered() {
+                $ui.tooltip_text(format!("Modified, the default is {:?}", $default.$($path).+));
+            }
+        }
+        config_section!(@items [$ui, $tree, $label, $original, $default] $($rest)*);
+    };
+    (@items [$ui:ident, $tree:ident, $label:ident, $original:ident, $default:ident] $field:literal => $body:block $($rest:tt)*) => {
+        if $tree.matches($field) {
+            let $label = $field;
+            $body
+        }
+        config_section!(@items [$ui, $tree, $label, $original, $default] $($rest)*);
+    };
+
+    ($ui:ident, $tree:ident, $label:ident, $original:ident => $default:ident, $($items:tt)*) => {
+        config_section!(@items [$ui, $tree, $label, $original, $default] $($items)*);
+    };
+}
+
+pub(super) fn render_config_ui(ui: &Ui, visible: bool, managers: &mut UiManagers) -> FallibleFn {
     let span_render_config = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "render_config").entered();
     if !visible {
         trace!(target: UI_TRACE_BUILD_INTERFACE, "not visible");
@@ -29,9 +208,20 @@ pub(super) fn render_config_ui(ui: &Ui, visible: bool) -> FallibleFn {
             warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report), "could not load config from disk");
             an_error_occurred(report);
             an_error_occurred(Report::msg("Test"))
+        } else {
+            report_keybinding_conflicts(&read_config_value(|config| config.runtime.keybindings));
         }
     }
 
+    explain_item(
+        ui,
+        indoc! {r"
+            Throws away the config in memory and loads the config file from disk again.
+
+            Useful after editing the config file by hand. Any changes made here that haven't been saved are lost
+        "},
+    );
+
     trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Save to Disk");
     if ui.button("Save to Disk") {
         debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Save to Disk pressed");
@@ -43,91 +233,197 @@ pub(super) fn render_config_ui(ui: &Ui, visible: bool) -> FallibleFn {
         }
     }
 
-    // This is a little iffy because we're cloning the config, then setting it later
-    // There is a chance that something will modify the config while we are modifying the copy,
-    // and then that change will be overwritten later.
-    // We can't (easily) work around this, but we can detect it by cloning the original,
-    // and then checking the original against the current when we go to update it
-    // If they differ, something was changed and we are overwriting it
+    explain_item(
+        ui,
+        indoc! {r"
+            Writes the current config to the config file on disk.
+
+            Changes made in this window take effect immediately, but are only kept after restarting once they've been saved
+        "},
+    );
+
+    {
+        let mut query = CONFIG_SEARCH.lock();
+        ui.input_text("Search", &mut query).hint("Filter settings...").build();
+    }
+    explain_item(ui, "Only shows the settings whose names contain this text (ignoring case). Clear it to show everything again");
+
+    // We clone the config, let the user modify the copy, then write it back
+    // There is a chance that something will modify the config while we are modifying the copy, and that change would be overwritten if we wrote it back blindly
+    // So we only write it back if the config is still the same as when we read it (compare-and-swap)
+    // If it isn't, we re-read the config and apply the user's changes on top of it, and try again
     let original_config = read_config_value(|config| config.clone());
     let mut modified_config = original_config.clone();
 
     modified_config.init.render(ui, true)?;
     modified_config.runtime.render(ui, true)?;
 
-    update_config(|cfg| {
-        //Do a check to make sure we aren't overwriting any other external changes
-        if cfg != &original_config {
-            warn!(
-                target: GENERAL_WARNING_NON_FATAL,
-                "original and current config didn't match: something modified config externally while config UI was being rendered"
-            );
-        }
-        *cfg = modified_config;
-    });
+    let fonts_path_changed = modified_config.runtime.resources.fonts_path != original_config.runtime.resources.fonts_path;
+    // Don't bother writing if nothing changed, that way we can't clobber anything
+    if modified_config != original_config && !write_back_config(&original_config, &modified_config) {
+        warn!(
+            target: GENERAL_WARNING_NON_FATAL,
+            attempts = MAX_CONFIG_UPDATE_ATTEMPTS,
+            "something kept modifying the config while the config UI was being rendered. discarding changes from config UI for this frame"
+        );
+    } else if fonts_path_changed {
+        // Has to be done after the config is updated, since the font manager reads the path from the config
+        debug!(target: UI_DEBUG_GENERAL, "fonts path changed, reloading fonts");
+        managers.toast = Some(match managers.font_manager.reload_list_from_resources() {
+            Ok(()) => Toast::new("Reloaded fonts from the new fonts folder", false),
+            Err(report) => {
+                warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report), "could not reload fonts after changing fonts path");
+                Toast::new(format!("Couldn't reload fonts: {report}"), true)
+            }
+        });
+    }
 
     span_render_config.exit();
     Ok(())
 }
 
-impl UiItem for InitTimeAppConfig {
-    fn render(&mut self, ui: &Ui, _visible: bool) -> FallibleFn {
-        let span_render = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "render_init_config", init_config=?self).entered();
-        trace!(target: UI_TRACE_BUILD_INTERFACE, "init config collapsing header");
-        let init_config_node = match ui.tree_node("Init Config") {
-            None => {
-                trace!(target: UI_TRACE_BUILD_INTERFACE, "init config collapsed");
-                return Ok(());
+/// Writes the changes the user made in the config UI (from `original` to `modified`) back to the global config, returning whether it succeeded
+///
+/// If the config was changed by something else in the meantime, the user's changes are re-applied on top of the new config, up to [MAX_CONFIG_UPDATE_ATTEMPTS] times
+fn write_back_config(original: &AppConfig, modified: &AppConfig) -> bool {
+    let mut expected = original.clone();
+    let mut new_config = modified.clone();
+    for attempt in 1..=MAX_CONFIG_UPDATE_ATTEMPTS {
+        if try_update_config(&expected, |cfg| *cfg = new_config.clone()) {
+            return true;
+        }
+        debug!(target: UI_DEBUG_GENERAL, attempt, "config was modified while the config UI was being rendered, re-applying changes to the fresh config");
+        expected = read_config_value(|config| config.clone());
+        new_config = match reapply_changes(&expected, original, modified) {
+            Ok(config) => config,
+            Err(report) => {
+                warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report), "could not re-apply config UI changes");
+                return false;
             }
-            Some(node) => node,
         };
+    }
+    false
+}
 
-        if let Some(ui_config_node) = ui.tree_node("UI") {
-            // With longer labels, the labels don't fit on the screen unless we give them a bit more width
-            let width_token = ui.push_item_width(ui.content_region_avail()[0] * 0.5);
-            let cfg = &mut self.ui_config;
-            if ui.checkbox("VSync", &mut cfg.vsync) {
-                trace!(target: UI_DEBUG_USER_INTERACTION, "changed vsync => {}", cfg.vsync);
-            }
-            if ui.checkbox("Start Maximised", &mut cfg.start_maximised) {
-                trace!(target: UI_DEBUG_USER_INTERACTION, "changed start_maximised => {}", cfg.start_maximised);
-            }
-            // Since we only have 3 possible values here, I find it acceptable to use hardcoded values
-            // This does mean that everything has to match perfectly, or bugs will happen
-            const HARDWARE_ACCELERATION_OPTIONS: [&'static str; 3] = ["Automatic", "Enabled", "Disabled"];
-            let mut hw_accel_idx = match cfg.hardware_acceleration {
-                None => 0,
-                Some(true) => 1,
-                Some(false) => 2,
-            };
-            if ui.combo_simple_string("Hardware acceleration", &mut hw_accel_idx, &HARDWARE_ACCELERATION_OPTIONS) {
-                let accel = match hw_accel_idx {
-                    0 => None,
-                    1 => Some(true),
-                    2 => Some(false),
-                    bad_value => unreachable!("There are only 3 option for hardware acceleration, but the value was out of range: {}", bad_value),
-                };
-                cfg.hardware_acceleration = accel;
-                trace!(target: UI_DEBUG_USER_INTERACTION, "changed hardware acceleration => {:?}", cfg.hardware_acceleration);
-            }
-            // Multisampling must be a power of 2, so fake it by showing the exponent
-            let mut multisampling_exponent: u16 = (cfg.multisampling as f32).log2() as u16;
-            if ui
-                .slider_config("Multisampling", 0, 4)
-                .display_format(format!("{}", 1u16 << multisampling_exponent))
-                .build(&mut multisampling_exponent)
-            {
-                cfg.multisampling = 1u16 << multisampling_exponent;
-                trace!(target: UI_DEBUG_USER_INTERACTION, "changed multisampling => {}", cfg.multisampling);
+impl UiItem for InitTimeAppConfig {
+    fn render(&mut self, ui: &Ui, _visible: bool) -> FallibleFn {
+        let span_render = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "render_init_config", init_config=?self).entered();
+        let query = CONFIG_SEARCH.lock().to_lowercase();
+        let tree = ConfigTree::new(&query);
+        // Compare against the config from before any changes this frame, since the fields are mutably borrowed while they're rendered
+        let original = *self;
+        let defaults = &DEFAULT_CONFIG.init;
+
+        config_section!(ui, tree, label, original => defaults, section "Init Config" {
+            section "UI" {
+                prelude {
+                    // With longer labels, the labels don't fit on the screen unless we give them a bit more width
+                    let _width_token = ui.push_item_width(ui.content_region_avail()[0] * 0.5);
+                    let cfg = &mut self.ui_config;
+                }
+                "VSync" [ui_config.vsync] => {
+                    if ui.checkbox(label, &mut cfg.vsync) {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed vsync => {}", cfg.vsync);
+                    }
+                }
+                "Start Maximised" [ui_config.start_maximised] => {
+                    if ui.checkbox(label, &mut cfg.start_maximised) {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed start_maximised => {}", cfg.start_maximised);
+                    }
+                }
+                "Hardware acceleration" [ui_config.hardware_acceleration] => {
+                    // Since we only have 3 possible values here, I find it acceptable to use hardcoded values
+                    // This does mean that everything has to match perfectly, or bugs will happen
+                    const HARDWARE_ACCELERATION_OPTIONS: [&'static str; 3] = ["Automatic", "Enabled", "Disabled"];
+                    let mut hw_accel_idx = match cfg.hardware_acceleration {
+                        None => 0,
+                        Some(true) => 1,
+                        Some(false) => 2,
+                    };
+                    if ui.combo_simple_string(label, &mut hw_accel_idx, &HARDWARE_ACCELERATION_OPTIONS) {
+                        let accel = match hw_accel_idx {
+                            0 => None,
+                            1 => Some(true),
+                            2 => Some(false),
+                            bad_value => unreachable!("There are only 3 option for hardware acceleration, but the value was out of range: {}", bad_value),
+                        };
+                        cfg.hardware_acceleration = accel;
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed hardware acceleration => {:?}", cfg.hardware_acceleration);
+                    }
+                }
+                "sRGB" [ui_config.srgb] => {
+                    if ui.checkbox(label, &mut cfg.srgb) {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed srgb => {}", cfg.srgb);
+                    }
+                }
+                "Multisampling" [ui_config.multisampling] => {
+                    // Multisampling must be a power of 2, so fake it by showing the exponent
+                    let mut multisampling_exponent: u16 = (cfg.multisampling as f32).log2() as u16;
+                    if ui
+                        .slider_config(label, 0, 4)
+                        .display_format(format!("{}", 1u16 << multisampling_exponent))
+                        .build(&mut multisampling_exponent)
+                    {
+                        cfg.multisampling = 1u16 << multisampling_exponent;
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed multisampling => {}", cfg.multisampling);
+                    }
+                }
+                "Renderer Backend" [ui_config.backend] => {
+                    let backend_names = Backend::ALL.map(Backend::name);
+                    let mut backend_idx = Backend::ALL.iter().position(|&backend| backend == cfg.backend).unwrap_or(0);
+                    if ui.combo_simple_string(label, &mut backend_idx, &backend_names) {
+                        cfg.backend = Backend::ALL[backend_idx];
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed backend => {:?}", cfg.backend);
+                    }
+                }
+                "HiDPI Mode" [ui_config.hidpi_mode] => {
+                    const HIDPI_MODE_OPTIONS: [&'static str; 3] = ["Default", "Rounded", "Locked"];
+                    let mut hidpi_idx = match cfg.hidpi_mode {
+                        HiDpiSetting::Default => 0,
+                        HiDpiSetting::Rounded => 1,
+                        HiDpiSetting::Locked(_) => 2,
+                    };
+                    if ui.combo_simple_string(label, &mut hidpi_idx, &HIDPI_MODE_OPTIONS) {
+                        cfg.hidpi_mode = match hidpi_idx {
+                            0 => HiDpiSetting::Default,
+                            1 => HiDpiSetting::Rounded,
+                            2 => HiDpiSetting::Locked(1.0),
+                            bad_value => unreachable!("There are only 3 options for HiDPI mode, but the value was out of range: {}", bad_value),
+                        };
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed hidpi mode => {:?}", cfg.hidpi_mode);
+                    }
+                }
+                "Limit Framerate" [ui_config.frame_limit] => {
+                    let mut limit_frames = cfg.frame_limit.is_some();
+                    if ui.checkbox(label, &mut limit_frames) {
+                        cfg.frame_limit = if limit_frames { Some(60.0) } else { None };
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed frame_limit => {:?}", cfg.frame_limit);
+                    }
+                    if let Some(frame_limit) = &mut cfg.frame_limit {
+                        if ui.slider("Frame Limit (fps)", 1.0, 360.0, frame_limit) {
+                            trace!(target: UI_DEBUG_USER_INTERACTION, "changed frame_limit => {}", frame_limit);
+                        }
+                    }
+                }
+                "Power Saving" [ui_config.power_saving] => {
+                    if ui.checkbox(label, &mut cfg.power_saving) {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed power_saving => {}", cfg.power_saving);
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Only redraw the UI when there's input, instead of constantly");
+                    }
+                }
+                "Scale Factor" => {
+                    if let HiDpiSetting::Locked(scale_factor) = &mut cfg.hidpi_mode {
+                        if ui.input_scalar(label, scale_factor).step(0.25).build() {
+                            *scale_factor = scale_factor.clamp(0.25, 8.0);
+                            trace!(target: UI_DEBUG_USER_INTERACTION, "changed locked hidpi scale factor => {}", scale_factor);
+                        }
+                    }
+                }
             }
+        });
 
-            width_token.end();
-            ui_config_node.end();
-        } else {
-            trace!(target: UI_TRACE_BUILD_INTERFACE, "ui config collapsed")
-        }
-
-        init_config_node.end();
         span_render.exit();
         Ok(())
     }
@@ -135,180 +431,434 @@ impl UiItem for InitTimeAppConfig {
 impl UiItem for RuntimeAppConfig {
     fn render(&mut self, ui: &Ui, _visible: bool) -> FallibleFn {
         let span_render = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "render_runtime_config", runtime_config=?self).entered();
-        trace!(target: UI_TRACE_BUILD_INTERFACE, "runtime config collapsing header");
-        let init_config_node = match ui.tree_node("Runtime Config") {
-            None => {
-                trace!(target: UI_TRACE_BUILD_INTERFACE, "runtime config collapsed");
-                return Ok(());
+        let query = CONFIG_SEARCH.lock().to_lowercase();
+        let tree = ConfigTree::new(&query);
+        // Compare against the config from before any changes this frame, since the fields are mutably borrowed while they're rendered
+        let original = self.clone();
+        let defaults = &DEFAULT_CONFIG.runtime;
+        // Copied so they can still be used while a part of the config is mutably borrowed
+        let colours = self.ui.colours;
+        let keybinding_names = self.keybindings.bindings().map(|(name, _, _)| name);
+        let default_keybindings = defaults.keybindings.bindings();
+        let modified_keybindings = original
+            .keybindings
+            .bindings()
+            .iter()
+            .zip(default_keybindings)
+            .filter(|((_, _, binding), (_, _, default))| binding != default)
+            .count();
+
+        config_section!(ui, tree, label, original => defaults, section "Runtime Config" {
+            "Auto-save Config on Exit" [auto_save_config_on_exit] => {
+                if ui.checkbox(label, &mut self.auto_save_config_on_exit) {
+                    trace!(target: UI_DEBUG_USER_INTERACTION, "changed auto_save_config_on_exit => {}", self.auto_save_config_on_exit);
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Saves the config to disk when the app exits. If disabled, changes are only kept if you press 'Save to Disk'");
+                }
             }
-            Some(node) => node,
-        };
-
-        if let Some(ui_config_node) = ui.tree_node("UI") {
-            // With longer labels, the labels don't fit on the screen unless we give them a bit more width
-            let width_token = ui.push_item_width(ui.content_region_avail()[0] * 0.5);
-            let ui_cfg = &mut self.ui;
-
-            if ui.slider("Font Oversampling", 1, 4, &mut ui_cfg.font_oversampling) {
-                trace!(target: UI_DEBUG_USER_INTERACTION, "changed font_oversampling => {}", ui_cfg.font_oversampling);
+            section "Engine" {
+                "Fixed Seed" [engine.seed] => {
+                    let mut fixed_seed = self.engine.seed.is_some();
+                    if ui.checkbox(label, &mut fixed_seed) {
+                        self.engine.seed = if fixed_seed { Some(0) } else { None };
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed seed => {:?}", self.engine.seed);
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Uses the same random numbers every time, so renders (and screenshots) can be reproduced exactly");
+                    }
+                    if let Some(seed) = &mut self.engine.seed {
+                        if ui.input_scalar("Seed", seed).build() {
+                            trace!(target: UI_DEBUG_USER_INTERACTION, "changed seed => {seed}");
+                        }
+                    }
+                }
             }
-
-            if let Some(frame_info_node) = ui.tree_node("Frame Info") {
-                // With longer labels, the labels don't fit on the screen unless we give them a bit more width
-                let width_token = ui.push_item_width(ui.content_region_avail()[0] * 0.5);
-                let frame_cfg = &mut ui_cfg.frame_info;
-
-                if ui.checkbox("Always show 0", &mut frame_cfg.min_always_at_zero) {
-                    trace!(target: UI_DEBUG_USER_INTERACTION, "changed min_always_at_zero => {}", frame_cfg.min_always_at_zero);
+            section "Messaging" {
+                "Thread Poll Interval" [messaging.thread_poll_interval_ms] => {
+                    let poll_interval = &mut self.messaging.thread_poll_interval_ms;
+                    if ui.slider_config(label, 1, 1000).flags(SliderFlags::LOGARITHMIC).display_format("%d ms").build(poll_interval) {
+                        // Zero would make the program thread spin without ever sleeping
+                        *poll_interval = (*poll_interval).clamp(1, 1000);
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed thread_poll_interval_ms => {poll_interval}");
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("How often the app checks that its threads are still running. Lower values notice a crashed thread sooner, but wake the CPU up more often");
+                    }
                 }
-                if ui.is_item_hovered() {
-                    ui.tooltip_text("When displaying frame rate and frame time graphs, whether to always have the bottom of the graph be at 0 (rather than the approximate smallest value)");
+            }
+            section "UI" {
+                prelude {
+                    // With longer labels, the labels don't fit on the screen unless we give them a bit more width
+                    let _width_token = ui.push_item_width(ui.content_region_avail()[0] * 0.5);
+                    let ui_cfg = &mut self.ui;
                 }
-
-                if slider_usize(ui, &mut frame_cfg.num_frames_to_track, SliderFlags::LOGARITHMIC, 69, MAX_FRAMES_TO_TRACK, "Max Tracked Frames", None) {
-                    trace!(target: UI_DEBUG_USER_INTERACTION, "changed num_frames_to_track => {}", frame_cfg.num_frames_to_track);
+                "Font Oversampling" [ui.font_oversampling] => {
+                    if ui.slider(label, 1, 4, &mut ui_cfg.font_oversampling) {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed font_oversampling => {}", ui_cfg.font_oversampling);
+                    }
                 }
-                if ui.is_item_hovered() {
-                    ui.tooltip_text(indoc! {r"
-                    The maximum amount of frames that can be stored at one time.\
-                    You probably want to leave this alone and modify [Num Displayed Frames] instead
-                    "});
+                "Font Size" [ui.font_size] => {
+                    if ui.slider(label, MIN_FONT_SIZE, MAX_FONT_SIZE, &mut ui_cfg.font_size) {
+                        ui_cfg.font_size = ui_cfg.font_size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed font_size => {}", ui_cfg.font_size);
+                    }
                 }
-
-                if slider_usize(
-                    ui,
-                    &mut frame_cfg.num_frames_to_display,
-                    SliderFlags::LOGARITHMIC,
-                    1,
-                    frame_cfg.num_frames_to_track,
-                    "Num Displayed Frames",
-                    None,
-                ) {
-                    trace!(target: UI_DEBUG_USER_INTERACTION, "changed num_frames_to_display => {}", frame_cfg.num_frames_to_display);
+                "Font Size Step" [ui.font_size_step] => {
+                    if ui.slider(label, 0.5, 10.0, &mut ui_cfg.font_size_step) {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed font_size_step => {}", ui_cfg.font_size_step);
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("How much the font size changes by when using the increase/decrease font size keybindings");
+                    }
                 }
-                if ui.is_item_hovered() {
-                    ui.tooltip_text(indoc! {r"
-                    The maximum amount of frames that will be displayed in the frame info interface.
-                    Cannot be set higher than [Max Tracked Frames], and will be soft-limited if there are insufficient frames to display
-                    (i.e. if only X frames are stored, only X will be shown, until X is at least this value)
-                    "});
+                "Max Displayed Errors" [ui.max_displayed_errors] => {
+                    if slider_usize(ui, &mut ui_cfg.max_displayed_errors, SliderFlags::LOGARITHMIC, 1, MAX_DISPLAYED_ERRORS, label, None) {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed max_displayed_errors => {}", ui_cfg.max_displayed_errors);
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("The maximum number of errors kept in the errors popup. When there are more, the oldest ones are dropped");
+                    }
                 }
-
-                if slider_usize(ui, &mut frame_cfg.chunked_average_smoothing_size, SliderFlags::LOGARITHMIC, 1, 256, "Frame Smoothing Interval", None) {
-                    trace!(
-                        target: UI_DEBUG_USER_INTERACTION,
-                        "changed chunked_average_smoothing_size => {}",
-                        frame_cfg.chunked_average_smoothing_size
-                    );
+                section "Frame Info" {
+                    prelude {
+                        // With longer labels, the labels don't fit on the screen unless we give them a bit more width
+                        let _width_token = ui.push_item_width(ui.content_region_avail()[0] * 0.5);
+                        let frame_cfg = &mut ui_cfg.frame_info;
+                    }
+                    "Always show 0" [ui.frame_info.min_always_at_zero] => {
+                        if ui.checkbox(label, &mut frame_cfg.min_always_at_zero) {
+                            trace!(target: UI_DEBUG_USER_INTERACTION, "changed min_always_at_zero => {}", frame_cfg.min_always_at_zero);
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text("When displaying frame rate and frame time graphs, whether to always have the bottom of the graph be at 0 (rather than the approximate smallest value)");
+                        }
+                    }
+                    "Max Tracked Frames" [ui.frame_info.num_frames_to_track] => {
+                        if slider_usize(ui, &mut frame_cfg.num_frames_to_track, SliderFlags::LOGARITHMIC, 69, MAX_FRAMES_TO_TRACK, label, None) {
+                            trace!(target: UI_DEBUG_USER_INTERACTION, "changed num_frames_to_track => {}", frame_cfg.num_frames_to_track);
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(indoc! {r"
+                            The maximum amount of frames that can be stored at one time.\
+                            You probably want to leave this alone and modify [Num Displayed Frames] instead
+                            "});
+                        }
+                    }
+                    "Num Displayed Frames" [ui.frame_info.num_frames_to_display] => {
+                        if slider_usize(ui, &mut frame_cfg.num_frames_to_display, SliderFlags::LOGARITHMIC, 1, frame_cfg.num_frames_to_track, label, None) {
+                            trace!(target: UI_DEBUG_USER_INTERACTION, "changed num_frames_to_display => {}", frame_cfg.num_frames_to_display);
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(indoc! {r"
+                            The maximum amount of frames that will be displayed in the frame info interface.
+                            Cannot be set higher than [Max Tracked Frames], and will be soft-limited if there are insufficient frames to display
+                            (i.e. if only X frames are stored, only X will be shown, until X is at least this value)
+                            "});
+                        }
+                    }
+                    "Frame Smoothing Interval" [ui.frame_info.chunked_average_smoothing_size] => {
+                        if slider_usize(ui, &mut frame_cfg.chunked_average_smoothing_size, SliderFlags::LOGARITHMIC, 1, 256, label, None) {
+                            trace!(
+                                target: UI_DEBUG_USER_INTERACTION,
+                                "changed chunked_average_smoothing_size => {}",
+                                frame_cfg.chunked_average_smoothing_size
+                            );
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(indoc! {r"
+                            When calculating the value range for plotting, the chunk size in which to average values.
+                            Higher values increase average more values, smoothing the min/max calculation (by reducing outliers), and de-focusing peaks and spikes
+                            "});
+                        }
+                    }
+                    "Lerp speed" [ui.frame_info.smooth_speed] => {
+                        if ui.slider_config(label, 0.00001, 0.1).flags(SliderFlags::LOGARITHMIC).build(&mut frame_cfg.smooth_speed) {
+                            trace!(target: UI_DEBUG_USER_INTERACTION, "changed smooth_speed => {}", frame_cfg.smooth_speed);
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(indoc! {r#"
+                            The amount by which to lerp between old values and new values, each frame. Smaller values will result in a smaller interpolation per-frame,
+                            Which will "slow down" the effect and result in more gradual changes
+                            "#});
+                        }
+                    }
                 }
-                if ui.is_item_hovered() {
-                    ui.tooltip_text(indoc! {r"
-                    When calculating the value range for plotting, the chunk size in which to average values.
-                    Higher values increase average more values, smoothing the min/max calculation (by reducing outliers), and de-focusing peaks and spikes
-                    "});
+                section "Colours" {
+                    prelude {
+                        let col_cfg = &mut ui_cfg.colours;
+                    }
+                    "Preset" => {
+                        if let Some(combo_token) = ui.begin_combo(label, "Apply a preset...") {
+                            for (name, theme) in Theme::builtin_presets() {
+                                if ui.selectable(format!("{name}##builtin")) {
+                                    debug!(target: UI_DEBUG_USER_INTERACTION, name, "applied built-in theme preset");
+                                    *col_cfg = theme;
+                                }
+                            }
+                            // Only bother loading the presets from disk while the combo is open
+                            match load_theme_presets() {
+                                Ok(presets) => {
+                                    if !presets.is_empty() {
+                                        ui.separator();
+                                    }
+                                    for (name, theme) in presets {
+                                        if ui.selectable(&name) {
+                                            debug!(target: UI_DEBUG_USER_INTERACTION, %name, "applied theme preset");
+                                            *col_cfg = theme;
+                                        }
+                                    }
+                                }
+                                Err(report) => {
+                                    ui.separator();
+                                    ui.text_colored(col_cfg.severity.warning, "Could not load saved presets");
+                                    trace!(target: UI_TRACE_BUILD_INTERFACE, report = format_report_display(&report), "failed to load theme presets");
+                                }
+                            }
+                            combo_token.end();
+                        }
+                    }
+                    "Save as Preset" => {
+                        let mut preset_name = THEME_PRESET_NAME.lock();
+                        ui.input_text("##preset_name", &mut preset_name).hint("Preset name").build();
+                        ui.same_line();
+                        if ui.button(label) {
+                            match save_theme_preset(&preset_name, col_cfg) {
+                                Ok(()) => {
+                                    debug!(target: UI_DEBUG_USER_INTERACTION, name = %preset_name, "saved theme preset");
+                                    preset_name.clear();
+                                }
+                                Err(report) => {
+                                    let report = report.wrap_err("could not save theme preset");
+                                    warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report), "could not save theme preset");
+                                    an_error_occurred(report);
+                                }
+                            }
+                        }
+                    }
+                    section "Text Colours" {
+                        "Normal" [ui.colours.text.normal] => { colour(ui, &mut col_cfg.text.normal, label); }
+                        "Subtle" [ui.colours.text.subtle] => { colour(ui, &mut col_cfg.text.subtle, label); }
+                        "Accent" [ui.colours.text.accent] => { colour(ui, &mut col_cfg.text.accent, label); }
+                        "Background" [ui.colours.text.background] => { colour(ui, &mut col_cfg.text.background, label); }
+                    }
+                    section "Severity Colours" {
+                        "Good" [ui.colours.severity.good] => { colour(ui, &mut col_cfg.severity.good, label); }
+                        "Neutral" [ui.colours.severity.neutral] => { colour(ui, &mut col_cfg.severity.neutral, label); }
+                        "Note" [ui.colours.severity.note] => { colour(ui, &mut col_cfg.severity.note, label); }
+                        "Warning" [ui.colours.severity.warning] => { colour(ui, &mut col_cfg.severity.warning, label); }
+                        "Very Bad" [ui.colours.severity.very_bad] => { colour(ui, &mut col_cfg.severity.very_bad, label); }
+                    }
+                    section "Value Colours" {
+                        "Error Event" [ui.colours.value.level_error] => { colour(ui, &mut col_cfg.value.level_error, label); }
+                        "Warn Event" [ui.colours.value.level_warn] => { colour(ui, &mut col_cfg.value.level_warn, label); }
+                        "Info Event" [ui.colours.value.level_info] => { colour(ui, &mut col_cfg.value.level_info, label); }
+                        "Debug Event" [ui.colours.value.level_debug] => { colour(ui, &mut col_cfg.value.level_debug, label); }
+                        "Trace Event" [ui.colours.value.level_trace] => { colour(ui, &mut col_cfg.value.level_trace, label); }
+                        separator;
+                        "Tracing Event Name" [ui.colours.value.tracing_event_name] => { colour(ui, &mut col_cfg.value.tracing_event_name, label); }
+                        "Tracing Field Name" [ui.colours.value.tracing_event_field_name] => { colour(ui, &mut col_cfg.value.tracing_event_field_name, label); }
+                        "Tracing Field Value" [ui.colours.value.tracing_event_field_value] => { colour(ui, &mut col_cfg.value.tracing_event_field_value, label); }
+                        separator;
+                        "Function" [ui.colours.value.function_name] => { colour(ui, &mut col_cfg.value.function_name, label); }
+                        "File Path" [ui.colours.value.file_location] => { colour(ui, &mut col_cfg.value.file_location, label); }
+                        separator;
+                        "Error Message" [ui.colours.value.error_message] => { colour(ui, &mut col_cfg.value.error_message, label); }
+                        separator;
+                        "Value Label" [ui.colours.value.value_label] => { colour(ui, &mut col_cfg.value.value_label, label); }
+                        separator;
+                        "Misc Value" [ui.colours.value.misc_value] => { colour(ui, &mut col_cfg.value.misc_value, label); }
+                        "Missing Value" [ui.colours.value.missing_value] => { colour(ui, &mut col_cfg.value.missing_value, label); }
+                        "Symbols" [ui.colours.value.symbol] => { colour(ui, &mut col_cfg.value.symbol, label); }
+                        "Numbers" [ui.colours.value.number] => { colour(ui, &mut col_cfg.value.number, label); }
+                    }
                 }
-
-                if ui.slider_config("Lerp speed", 0.00001, 0.1).flags(SliderFlags::LOGARITHMIC).build(&mut frame_cfg.smooth_speed) {
-                    trace!(target: UI_DEBUG_USER_INTERACTION, "changed smooth_speed => {}", frame_cfg.smooth_speed);
+            }
+            section "Resources" {
+                "Fonts Folder" [resources.fonts_path] => {
+                    // Edit a copy, and only commit it once the user is done typing, otherwise the fonts would be reloaded (and probably fail) on every keystroke
+                    // ImGui keeps its own copy of the text while the input is active, so the copy being reset each frame doesn't lose what's been typed
+                    let mut fonts_path = self.resources.fonts_path.clone();
+                    ui.input_text(label, &mut fonts_path).build();
+                    if ui.is_item_deactivated_after_edit() {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed fonts_path => {fonts_path}");
+                        self.resources.fonts_path = fonts_path;
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("The folder fonts are loaded from, relative to the resources folder. Fonts are reloaded when this is changed");
+                    }
+                    match resource_folder_path(ResourceKind::Fonts) {
+                        Ok(path) => ui.text_colored(colours.text.subtle, path.display().to_string()),
+                        Err(report) => ui.text_colored(colours.severity.warning, format!("Couldn't find the fonts folder: {report}")),
+                    }
                 }
-                if ui.is_item_hovered() {
-                    ui.tooltip_text(indoc! {r#"
-                    The amount by which to lerp between old values and new values, each frame. Smaller values will result in a smaller interpolation per-frame,
-                    Which will "slow down" the effect and result in more gradual changes
-                    "#});
+                "Hot-reload" [resources.hot_reload] => {
+                    if ui.checkbox(label, &mut self.resources.hot_reload) {
+                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed hot_reload => {}", self.resources.hot_reload);
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Automatically reload resources (e.g. fonts) when their files are changed");
+                    }
                 }
-
-                width_token.end();
-                frame_info_node.end();
-            } else {
-                trace!(target: UI_TRACE_BUILD_INTERFACE, "frame info config collapsed")
             }
-
-            if let Some(colours_node) = ui.tree_node("Colours") {
-                let col_cfg = &mut ui_cfg.colours;
-                macro_rules! colour {
-                    ($name:expr, $field:expr) => {
-                        colour(ui, &mut $field, $name);
-                    };
+            custom "Keybindings" (keybinding_names) modified (modified_keybindings) |tree| {
+                let mut bindings = self.keybindings.bindings_mut();
+                // Copy the values out first, so we can check for conflicts while modifying them
+                let values: Vec<KeyBinding> = bindings.iter().map(|(_, _, binding)| **binding).collect();
+                let mut rebound = false;
+
+                if let Some(table_token) = ui.begin_table_with_flags("keybindings table", 3, TableFlags::SIZING_FIXED_FIT) {
+                    for (index, (name, description, binding)) in bindings.iter_mut().enumerate() {
+                        if !tree.matches(name) {
+                            continue;
+                        }
+                        let (_, _, default) = default_keybindings[index];
+                        let modified = values[index] != default;
+                        ui.table_next_row();
+                        ui.table_next_column();
+                        ui.text_colored(if modified { tree.modified_colour } else { colours.value.value_label }, *name);
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(*description);
+                            if modified {
+                                ui.tooltip_text(format!("Modified, the default is {default}"));
+                            }
+                        }
+
+                        ui.table_next_column();
+                        if keybinding_capture_target() == Some(*name) {
+                            ui.text_colored(colours.text.accent, "Press a key (Escape to cancel)...");
+                            if let Some(new_binding) = poll_captured_keybinding(ui) {
+                                trace!(target: UI_DEBUG_USER_INTERACTION, "changed keybinding {name} => {new_binding}");
+                                **binding = new_binding;
+                                rebound = true;
+                            }
+                        } else {
+                            ui.text_colored(colours.value.misc_value, binding.to_string());
+                        }
+
+                        ui.table_next_column();
+                        if ui.small_button(format!("Rebind##{name}")) {
+                            debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Rebind {name} pressed");
+                            begin_keybinding_capture(*name);
+                        }
+                        let conflicts = values.iter().enumerate().any(|(other_index, other)| other_index != index && *other == values[index]);
+                        if conflicts {
+                            ui.same_line();
+                            ui.text_colored(colours.severity.warning, "Conflicts with another keybinding");
+                        }
+                    }
+                    table_token.end();
                 }
-                fn colour(ui: &Ui, field: &mut Colour, name: &str) {
-                    //TODO: Should this be another part of the config?
-                    let picker = ui
-                        .color_edit4_config(name, field)
-                        .options(true)
-                        .alpha_bar(true)
-                        .small_preview(true)
-                        .hdr(false)
-                        .picker(true)
-                        .label(false)
-                        .tooltip(true)
-                        .alpha(true)
-                        .preview(ColorPreview::HalfAlpha);
-                    if picker.build() {
-                        trace!(target: UI_DEBUG_USER_INTERACTION, "changed ui colour {name} => {field:?}");
-                    }
-                    // Colour the label of the colour picker, so we get a bit of an example
-                    ui.same_line();
-                    ui.text_colored(*field,name);
+                if rebound {
+                    report_keybinding_conflicts(&self.keybindings);
                 }
+            }
+            custom "Tracing" (["Target Filters", "Add Filter"]) modified (usize::from(original.tracing != defaults.tracing)) |_tree| {
+                let filters = &mut self.tracing.target_filters;
+                // These take effect straight away, since the tracing filter reads the config again for every event
+                ui.text_colored(
+                    colours.text.subtle,
+                    "Target filters (the first filter matching an event's target is used, targets without a filter are logged)",
+                );
+                let mut to_delete = None;
+
+                if let Some(table_token) = ui.begin_table_with_flags("target filters table", 3, TableFlags::SIZING_FIXED_FIT | TableFlags::ROW_BG) {
+                    ui.table_setup_column("Target");
+                    ui.table_setup_column("Enabled");
+                    ui.table_setup_column("");
+                    ui.table_headers_row();
+
+                    // Shown so the user knows why they can't silence these, but they can't be changed
+                    for target in UNFILTERABLE_TARGETS {
+                        ui.table_next_row();
+                        ui.table_next_column();
+                        ui.text_colored(colours.value.missing_value, short_target_name(target));
+                        ui.table_next_column();
+                        let disabled_token = ui.begin_disabled(true);
+                        ui.checkbox(format!("##locked_{target}"), &mut true);
+                        disabled_token.end();
+                        ui.table_next_column();
+                        ui.text_colored(colours.text.subtle, "Locked");
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text("Warnings and errors are always logged, since filtering them could hide problems with the config itself");
+                        }
+                    }
 
-                if let Some(token) = ui.tree_node("Text Colours") {
-                    colour!("Normal", col_cfg.text.normal);
-                    colour!("Subtle", col_cfg.text.subtle);
-                    colour!("Accent", col_cfg.text.accent);
-                    token.end();
-                } else {
-                    trace!(target: UI_TRACE_BUILD_INTERFACE, "text colours header collapsed");
+                    for (index, filter) in filters.iter_mut().enumerate() {
+                        ui.table_next_row();
+                        ui.table_next_column();
+                        ui.text_colored(colours.value.value_label, short_target_name(&filter.target));
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(&filter.target);
+                        }
+                        ui.table_next_column();
+                        if ui.checkbox(format!("##enabled_{index}"), &mut filter.enabled) {
+                            debug!(target: UI_DEBUG_USER_INTERACTION, filter_target = filter.target, enabled = filter.enabled, "changed target filter");
+                        }
+                        ui.table_next_column();
+                        if ui.small_button(format!("Delete##{index}")) {
+                            debug!(target: UI_DEBUG_USER_INTERACTION, filter_target = filter.target, "[Button] Delete target filter pressed");
+                            to_delete = Some(index);
+                        }
+                    }
+                    table_token.end();
                 }
-                if let Some(token) = ui.tree_node("Severity Colours") {
-                    colour!("Good", col_cfg.severity.good);
-                    colour!("Neutral", col_cfg.severity.neutral);
-                    colour!("Note", col_cfg.severity.note);
-                    colour!("Warning", col_cfg.severity.warning);
-                    colour!("Very Bad", col_cfg.severity.very_bad);
-                    token.end();
-                } else {
-                    trace!(target: UI_TRACE_BUILD_INTERFACE, "severity colours header collapsed");
+                if let Some(index) = to_delete {
+                    filters.remove(index);
                 }
-                if let Some(token) = ui.tree_node("Value Colours") {
-                    colour!("Error Event", col_cfg.value.level_error);
-                    colour!("Warn Event", col_cfg.value.level_warn);
-                    colour!("Info Event", col_cfg.value.level_info);
-                    colour!("Debug Event", col_cfg.value.level_debug);
-                    colour!("Trace Event", col_cfg.value.level_trace);
-                    ui.separator();
-                    colour!("Tracing Event Name", col_cfg.value.tracing_event_name);
-                    colour!("Tracing Field Name", col_cfg.value.tracing_event_field_name);
-                    colour!("Tracing Field Value", col_cfg.value.tracing_event_field_value);
-                    ui.separator();
-                    colour!("Function", col_cfg.value.function_name);
-                    colour!("File Path", col_cfg.value.file_location);
-                    ui.separator();
-                    colour!("Error Message", col_cfg.value.error_message);
-                    ui.separator();
-                    colour!("Value Label", col_cfg.value.value_label);
-                    ui.separator();
-                    colour!("Misc Value", col_cfg.value.misc_value);
-                    colour!("Missing Value", col_cfg.value.missing_value);
-                    colour!("Symbols", col_cfg.value.symbol);
-                    colour!("Numbers", col_cfg.value.number);
-                    token.end();
-                } else {
-                    trace!(target: UI_TRACE_BUILD_INTERFACE, "value colours header collapsed");
+
+                // Targets that already have a filter (or can't have one) would never match, so don't offer them
+                let available = ALL_TARGETS
+                    .iter()
+                    .filter(|target| !UNFILTERABLE_TARGETS.contains(target) && !filters.iter().any(|filter| filter.target == **target))
+                    .collect::<Vec<_>>();
+                let disabled_token = ui.begin_disabled(available.is_empty());
+                if let Some(combo_token) = ui.begin_combo("Add Filter", "Choose a target...") {
+                    for target in available {
+                        if ui.selectable(short_target_name(target)) {
+                            debug!(target: UI_DEBUG_USER_INTERACTION, filter_target = target, "added target filter");
+                            // Disabled, since the main reason to add a filter is to silence a noisy target
+                            filters.push(LogTargetFilter::new(target, false));
+                        }
+                    }
+                    combo_token.end();
                 }
+                disabled_token.end();
             }
-            width_token.end();
-            ui_config_node.end();
-        } else {
-            trace!(target: UI_TRACE_BUILD_INTERFACE, "ui config collapsed")
-        }
+        });
 
-        init_config_node.end();
         span_render.exit();
         Ok(())
     }
 }
 
+/// Displays a colour picker for one of the [Theme]'s colours, with the label drawn in that colour so there's an example of what it looks like
+fn colour(ui: &Ui, field: &mut Colour, name: &str) {
+    //TODO: Should this be another part of the config?
+    let picker = ui
+        .color_edit4_config(name, field)
+        .options(true)
+        .alpha_bar(true)
+        .small_preview(true)
+        .hdr(false)
+        .picker(true)
+        .label(false)
+        .tooltip(true)
+        .alpha(true)
+        .preview(ColorPreview::HalfAlpha);
+    if picker.build() {
+        trace!(target: UI_DEBUG_USER_INTERACTION, "changed ui colour {name} => {field:?}");
+    }
+    // Colour the label of the colour picker, so we get a bit of an example
+    ui.same_line();
+    ui.text_colored(*field, name);
+}
+
+/// Strips the common prefix off of a tracing target, so it's short enough to show in the UI
+fn short_target_name(target: &str) -> &str {
+    target.rsplit("::").next().unwrap_or(target)
+}
+
 fn slider_usize(ui: &Ui, val: &mut usize, flags: SliderFlags, min: usize, max: usize, label: &str, display_format: Option<&str>) -> bool {
     let mut compat_u64 = *val as u64;
     let mut slider = ui.slider_config(label, min as u64, max as u64).flags(flags);
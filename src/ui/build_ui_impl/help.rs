@@ -0,0 +1,41 @@
+//! The help window, which lists every keybinding and what it does
+
+use crate::config::read_config_value;
+use crate::helper::logging::event_targets::*;
+use crate::FallibleFn;
+use imgui::{TableFlags, Ui};
+use tracing::trace;
+
+/// Renders the help window. The list is generated from [KeybindingsConfig::bindings()](crate::config::run_time::keybindings_config::KeybindingsConfig::bindings), so it stays in sync with the keybindings
+pub(super) fn render_help_window(ui: &Ui, visible: bool) -> FallibleFn {
+    if !visible {
+        trace!(target: UI_TRACE_BUILD_INTERFACE, "help window not visible");
+        return Ok(());
+    }
+    let (keybindings, colours) = read_config_value(|config| (config.runtime.keybindings, config.runtime.ui.colours));
+
+    ui.text_colored(
+        colours.text.normal,
+        format!("Press {} to turn on explain mode, then hover over things to find out what they do", keybindings.toggle_explain_mode),
+    );
+    ui.text_colored(colours.text.subtle, "Keybindings can be changed in the config window");
+    ui.separator();
+
+    if let Some(table_token) = ui.begin_table_with_flags("help keybindings table", 3, TableFlags::SIZING_FIXED_FIT | TableFlags::ROW_BG) {
+        ui.table_setup_column("Action");
+        ui.table_setup_column("Keybinding");
+        ui.table_setup_column("Description");
+        ui.table_headers_row();
+        for (name, description, binding) in keybindings.bindings() {
+            ui.table_next_row();
+            ui.table_next_column();
+            ui.text_colored(colours.value.value_label, name);
+            ui.table_next_column();
+            ui.text_colored(colours.value.misc_value, binding.to_string());
+            ui.table_next_column();
+            ui.text_colored(colours.text.normal, description);
+        }
+        table_token.end();
+    }
+    Ok(())
+}
@@ -1,35 +1,73 @@
+mod about;
+mod command_palette;
 mod config_ui_impl;
+mod help;
+mod quit_confirmation;
 mod shared;
+mod status_bar;
 mod ui_management;
+mod window_registry;
 
-use crate::config::read_config_value;
+use about::{render_about_popup, show_about_popup};
+use command_palette::{render_command_palette, toggle_command_palette};
+use quit_confirmation::render_quit_confirmation_popup;
+use crate::config::compile_time::ui_config::TOAST_DURATION;
+use crate::config::run_time::ui_config::theme::Theme;
+use crate::config::{read_config_value, update_config};
+use crate::engine::EngineStatus;
 use crate::helper::logging::event_targets::*;
 use crate::helper::logging::span_time_elapsed_field::SpanTimeElapsedField;
-use crate::program::thread_messages::ProgramThreadMessage::QuitAppNoError;
-use crate::program::thread_messages::QuitAppNoErrorReason::QuitInteractionByUser;
-use crate::program::thread_messages::ThreadMessage::Program;
+use crate::program::thread_messages::ThreadMessage::Engine;
 use crate::program::thread_messages::*;
 use crate::ui::build_ui_impl::shared::error_display::render_errors_popup;
 use crate::ui::ui_data::UiData;
 use crate::ui::ui_system::UiManagers;
 use crate::FallibleFn;
-use config_ui_impl::render_config_ui;
+use imgui::{Condition, WindowFlags};
 use indoc::indoc;
 use multiqueue2::{BroadcastReceiver, BroadcastSender};
-use shared::input::handle_shortcut;
-use shared::menu_utils::{menu, toggle_menu_item};
-use shared::window_utils::{build_window, build_window_fn};
+use shared::explain_mode::{explain_mode_enabled, toggle_explain_mode};
+use shared::input::{handle_shortcut, shortcut_pressed};
+use shared::menu_utils::{menu, radio_menu_item, toggle_menu_item};
 use tracing::field::*;
 use tracing::*;
+use window_registry::WINDOWS;
+
+pub(super) use quit_confirmation::request_quit;
+pub(super) use shared::error_display::an_error_occurred;
+pub(super) use status_bar::{render_status_bar, status_bar_height};
+pub(super) use window_registry::window_title;
 
 pub trait UiItem {
     fn render(&mut self, ui: &imgui::Ui, visible: bool) -> FallibleFn;
 }
 
+/// Shows the current [Toast](crate::ui::ui_system::Toast) (if there is one) in the bottom-right corner of the window, until it expires
+fn render_toast(ui: &imgui::Ui, managers: &mut UiManagers) {
+    let toast = match &managers.toast {
+        Some(toast) if toast.shown_at.elapsed() < TOAST_DURATION => toast,
+        Some(_) => {
+            trace!(target: UI_TRACE_BUILD_INTERFACE, "toast expired");
+            managers.toast = None;
+            return;
+        }
+        None => return,
+    };
+    let colours = read_config_value(|config| config.runtime.ui.colours);
+    let colour = if toast.is_warning { colours.severity.warning } else { colours.text.normal };
+    let [width, height] = ui.io().display_size;
+    ui.window("Toast")
+        .position([width - 10.0, height - 10.0], Condition::Always)
+        .position_pivot([1.0, 1.0])
+        .flags(WindowFlags::NO_DECORATION | WindowFlags::ALWAYS_AUTO_RESIZE | WindowFlags::NO_INPUTS | WindowFlags::NO_SAVED_SETTINGS | WindowFlags::NO_FOCUS_ON_APPEARING | WindowFlags::NO_NAV)
+        .build(|| ui.text_colored(colour, &toast.message));
+}
+
 pub(super) fn build_ui(
     ui: &imgui::Ui,
     managers: &mut UiManagers,
     data: &mut UiData,
+    engine_status: EngineStatus,
     message_sender: &BroadcastSender<ThreadMessage>,
     _message_receiver: &BroadcastReceiver<ThreadMessage>,
 ) -> FallibleFn {
@@ -38,11 +76,6 @@ pub(super) fn build_ui(
     let timer = SpanTimeElapsedField::new();
     let span_build_ui = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "build_ui", elapsed = Empty).entered();
 
-    // refs to reduce clutter
-    let show_demo_window = &mut data.windows.show_demo_window;
-    let show_metrics_window = &mut data.windows.show_metrics_window;
-    let show_ui_management_window = &mut data.windows.show_ui_management_window;
-    let show_config_window = &mut data.windows.show_config_window;
     let keys = read_config_value(|config| config.runtime.keybindings);
 
     trace_span!(target: UI_TRACE_BUILD_INTERFACE, "main_menu_bar").in_scope(|| {
@@ -57,98 +90,177 @@ pub(super) fn build_ui(
         trace!(target: UI_TRACE_BUILD_INTERFACE, "building main menu bar");
 
         menu(ui, "Tools", || {
-            toggle_menu_item(
-                ui,
-                "Demo Window",
-                show_demo_window,
-                &keys.toggle_demo_window.to_string(),
-                indoc! {r"
-                Toggles the ImGUI demo window.
+            for window in &WINDOWS {
+                toggle_menu_item(ui, window.title, data.windows.open_mut(window.id), &(window.keybinding)(&keys).to_string(), window.description);
+            }
 
-                The demo window demonstrates the features of Dear ImGUI, and provides some debugging tools for debugging ImGUI
-            "},
-            )?;
-            toggle_menu_item(
+            // These aren't really toggles, so pass in a throwaway bool and just use whether the item was clicked
+            if toggle_menu_item(
                 ui,
-                "Metrics",
-                show_metrics_window,
-                &keys.toggle_metrics_window.to_string(),
+                "Reset Window Layout",
+                &mut false,
+                "",
                 indoc! {r"
-                Toggles the ImGUI metrics window.
+                    Resets the docking layout back to the default.
 
-                The metrics window shows statistics and metrics about Dear ImGUI
-            "},
-            )?;
-            toggle_menu_item(
-                ui,
-                "Config",
-                show_config_window,
-                &keys.toggle_config_window.to_string(),
-                indoc! {r"
-                Shows/hides the config window.
+                    Windows that aren't part of the default layout are left where they are
+                "},
+            ) {
+                debug!(target: UI_DEBUG_USER_INTERACTION, "user clicked reset window layout menu item");
+                managers.apply_default_layout = true;
+            }
 
-                The config window allows modifying the app configuration. Very much WIP
-            "},
-            )?;
-            toggle_menu_item(
+            // Same trick as the quit handling below: the screenshot is taken after the frame is drawn, so just request one
+            if toggle_menu_item(
                 ui,
-                "UI Management",
-                show_ui_management_window,
-                &keys.toggle_ui_managers_window.to_string(),
+                "Save Screenshot",
+                &mut false,
+                &keys.save_screenshot.to_string(),
                 indoc! {r"
-                    Toggles the UI management window.
+                    Saves a screenshot of the whole window to the screenshots folder.
 
-                    The UI management window allows you to control the UI, such as changing the font.
-            "},
-            )?;
+                    Useful for bug reports
+                "},
+            ) {
+                debug!(target: UI_DEBUG_USER_INTERACTION, "user clicked screenshot menu item, requesting screenshot");
+                managers.screenshot_requested = true;
+            }
 
             // Semi-hacky quit handling
-            // Makes a toggle and if it's set to true, sends quit message to program
-            let mut exit = false;
-            toggle_menu_item(
+            // If the item is clicked, sends quit message to program
+            if toggle_menu_item(
                 ui,
                 "Exit",
-                &mut exit, // Doesn't show any checkboxes or anything
+                &mut false, // Doesn't show any checkboxes or anything
                 &keys.exit_app.to_string(),
                 indoc! {r"
                     Exits the application.
 
                     Exactly the same as clicking the close button
                 "},
-            )?;
-            if exit {
-                debug!(target: UI_DEBUG_USER_INTERACTION, "user clicked quit menu item, sending quit signals");
-                send_message(Program(QuitAppNoError(QuitInteractionByUser)), message_sender)?;
-                debug!(target: UI_DEBUG_GENERAL, "ui should quit soon");
+            ) {
+                debug!(target: UI_DEBUG_USER_INTERACTION, "user clicked quit menu item");
+                request_quit(message_sender)?;
             }
 
             Ok(())
         })?; //end Tools menu
 
+        menu(ui, "Theme", || {
+            let mut theme = read_config_value(|config| config.runtime.ui.colours);
+            for (name, preset) in Theme::builtin_presets() {
+                if radio_menu_item(ui, name, &mut theme, preset, "", "Switches to this built-in colour theme. Custom themes can be made in the config window") {
+                    update_config(|config| config.runtime.ui.colours = theme);
+                }
+            }
+            Ok(())
+        })?; //end Theme menu
+
+        menu(ui, "Help", || {
+            let mut explain_mode = explain_mode_enabled();
+            if toggle_menu_item(
+                ui,
+                "Explain Mode",
+                &mut explain_mode,
+                &keys.toggle_explain_mode.to_string(),
+                indoc! {r"
+                    Toggles explain mode.
+
+                    While explain mode is on, hovering over a control (like this one) shows a longer explanation of what it does
+                "},
+            ) {
+                toggle_explain_mode();
+            }
+
+            if toggle_menu_item(ui, "About", &mut false, "", "Shows information about this build of the app, such as the version and git commit") {
+                debug!(target: UI_DEBUG_USER_INTERACTION, "user clicked about menu item");
+                show_about_popup();
+            }
+            Ok(())
+        })?; //end Help menu
+
+        // Straight on the menu bar instead of in a menu, so it works like a toolbar button
+        let engine_paused = engine_status == EngineStatus::Paused;
+        if toggle_menu_item(
+            ui,
+            if engine_paused { "Resume Engine" } else { "Pause Engine" },
+            &mut false,
+            "",
+            indoc! {r"
+                Pauses (or resumes) the engine, without quitting the app.
+
+                Mostly useful for debugging, since it freezes whatever the engine was doing
+            "},
+        ) {
+            let message = if engine_paused { EngineThreadMessage::Resume } else { EngineThreadMessage::Pause };
+            debug!(target: UI_DEBUG_USER_INTERACTION, ?message, "user toggled engine pause");
+            send_message(Engine(message), message_sender)?;
+        }
+        // Stepping only makes sense while paused (the engine ignores it otherwise)
+        let disabled_token = ui.begin_disabled(!engine_paused);
+        if toggle_menu_item(
+            ui,
+            "Step Engine",
+            &mut false,
+            &keys.step_engine.to_string(),
+            indoc! {r"
+                Runs the engine loop exactly once, then pauses it again.
+
+                Only works while the engine is paused
+            "},
+        ) {
+            debug!(target: UI_DEBUG_USER_INTERACTION, "user clicked step engine menu item");
+            send_message(Engine(EngineThreadMessage::Step), message_sender)?;
+        }
+        disabled_token.end();
+
+        if explain_mode_enabled() {
+            let colours = read_config_value(|config| config.runtime.ui.colours);
+            ui.text_colored(colours.severity.note, format!("Explain mode is on, hover over things for help ({} to turn off)", keys.toggle_explain_mode));
+        }
+
         main_menu_bar_token.end();
         FallibleFn::Ok(())
     })?; // end main menu
 
-    if *show_demo_window {
-        trace_span!(target: UI_TRACE_BUILD_INTERFACE, "show_demo_window").in_scope(|| ui.show_demo_window(show_demo_window));
-    } else {
-        trace!(target: UI_TRACE_BUILD_INTERFACE, "demo window hidden");
-    }
-    if *show_metrics_window {
-        trace_span!(target: UI_TRACE_BUILD_INTERFACE, "show_metrics_window").in_scope(|| ui.show_metrics_window(show_metrics_window));
-    } else {
-        trace!(target: UI_TRACE_BUILD_INTERFACE, "metrics window hidden");
+    for window in &WINDOWS {
+        (window.render)(ui, window.title, managers, data.windows.open_mut(window.id))?;
     }
-    build_window("UI Management", managers, show_ui_management_window, ui)?;
-    build_window_fn("Config", render_config_ui, show_config_window, ui)?;
+    render_command_palette(ui, data, managers, message_sender)?;
     render_errors_popup(ui);
+    render_about_popup(ui);
+    render_quit_confirmation_popup(ui, message_sender)?;
+    render_toast(ui, managers);
 
     trace_span!(target: UI_TRACE_USER_INPUT, "handle_input").in_scope(|| {
-        handle_shortcut(ui, "show demo window", &keys.toggle_demo_window, show_demo_window);
-        handle_shortcut(ui, "show config window", &keys.toggle_config_window, show_config_window);
-        handle_shortcut(ui, "show ui management window", &keys.toggle_ui_managers_window, show_ui_management_window);
-        handle_shortcut(ui, "show metrics window", &keys.toggle_metrics_window, show_metrics_window);
-    });
+        for window in &WINDOWS {
+            handle_shortcut(ui, window.title, &(window.keybinding)(&keys), &keys, data.windows.open_mut(window.id));
+        }
+
+        let font_size_step = read_config_value(|config| config.runtime.ui.font_size_step);
+        if shortcut_pressed(ui, "increase font size", &keys.increase_font_size, &keys) {
+            managers.font_manager.adjust_size(font_size_step);
+        }
+        if shortcut_pressed(ui, "decrease font size", &keys.decrease_font_size, &keys) {
+            managers.font_manager.adjust_size(-font_size_step);
+        }
+        if shortcut_pressed(ui, "toggle explain mode", &keys.toggle_explain_mode, &keys) {
+            toggle_explain_mode();
+        }
+        if shortcut_pressed(ui, "toggle command palette", &keys.toggle_command_palette, &keys) {
+            toggle_command_palette();
+        }
+        if shortcut_pressed(ui, "save screenshot", &keys.save_screenshot, &keys) {
+            managers.screenshot_requested = true;
+        }
+        if shortcut_pressed(ui, "exit app", &keys.exit_app, &keys) {
+            request_quit(message_sender)?;
+        }
+        if engine_status == EngineStatus::Paused && shortcut_pressed(ui, "step engine", &keys.step_engine, &keys) {
+            send_message(Engine(EngineThreadMessage::Step), message_sender)?;
+        }
+        FallibleFn::Ok(())
+    })?;
 
     span_build_ui.record("elapsed", display(timer));
     span_build_ui.exit();
@@ -0,0 +1,93 @@
+//! Asks the user what to do with unsaved config changes before the app quits
+
+use crate::config::{config_load_failed, has_unsaved_changes, read_config_value, save_config_to_disk};
+use crate::helper::logging::event_targets::*;
+use crate::helper::logging::format_report_display;
+use crate::program::thread_messages::ProgramThreadMessage::QuitAppNoError;
+use crate::program::thread_messages::QuitAppNoErrorReason::QuitInteractionByUser;
+use crate::program::thread_messages::ThreadMessage::Program;
+use crate::program::thread_messages::{send_message, ThreadMessage};
+use crate::ui::build_ui_impl::shared::error_display::an_error_occurred;
+use crate::FallibleFn;
+use color_eyre::eyre::WrapErr;
+use imgui::Ui;
+use multiqueue2::BroadcastSender;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use tracing::{debug, trace, trace_span, warn};
+
+/// Set when the popup should be opened, since quitting can be requested from outside the frame (when the window's close button is clicked)
+static SHOW_QUIT_CONFIRMATION: AtomicBool = AtomicBool::new(false);
+
+const MODAL_NAME: &str = "Unsaved Changes";
+
+/// Quits the app, or if there are config changes that would be lost, asks the user what to do with them first
+///
+/// Everything that quits the app (the close button, the exit keybinding, menu item and command) should go through this
+pub(in crate::ui) fn request_quit(message_sender: &BroadcastSender<ThreadMessage>) -> FallibleFn {
+    // With auto-save enabled, the config is saved as the app exits anyway, so nothing can be lost
+    // Unless the config file couldn't be loaded, in which case auto-save is skipped so it doesn't get overwritten
+    let auto_saves = read_config_value(|config| config.runtime.auto_save_config_on_exit) && !config_load_failed();
+    if auto_saves || !has_unsaved_changes() {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "no unsaved config changes, sending quit signal");
+        return send_quit_message(message_sender);
+    }
+    debug!(target: UI_DEBUG_USER_INTERACTION, "config has unsaved changes, asking user before quitting");
+    SHOW_QUIT_CONFIRMATION.store(true, Relaxed);
+    Ok(())
+}
+
+fn send_quit_message(message_sender: &BroadcastSender<ThreadMessage>) -> FallibleFn {
+    send_message(Program(QuitAppNoError(QuitInteractionByUser)), message_sender)?;
+    debug!(target: UI_DEBUG_GENERAL, "ui should quit soon");
+    Ok(())
+}
+
+pub(super) fn render_quit_confirmation_popup(ui: &Ui, message_sender: &BroadcastSender<ThreadMessage>) -> FallibleFn {
+    if SHOW_QUIT_CONFIRMATION.swap(false, Relaxed) {
+        trace!(target: UI_TRACE_BUILD_INTERFACE, "opening quit confirmation popup");
+        ui.open_popup(MODAL_NAME);
+    }
+
+    let _span = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "quit_confirmation_modal").entered();
+    let popup_token = match ui.modal_popup_config(MODAL_NAME).always_auto_resize(true).begin_popup() {
+        None => {
+            trace!(target: UI_TRACE_BUILD_INTERFACE, "quit confirmation modal not visible");
+            return Ok(());
+        }
+        Some(token) => token,
+    };
+
+    ui.text("The config has been changed since it was last saved.\nDo you want to save the changes before quitting?");
+    ui.separator();
+
+    trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Save and quit");
+    if ui.button("Save and quit") {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Save and quit pressed");
+        ui.close_current_popup();
+        match save_config_to_disk().wrap_err("could not save config to disk") {
+            Ok(()) => send_quit_message(message_sender)?,
+            // Don't quit, otherwise the changes would be lost when the user asked for them to be kept
+            Err(report) => {
+                warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report), "not quitting");
+                an_error_occurred(report);
+            }
+        }
+    }
+    ui.same_line();
+    trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Quit without saving");
+    if ui.button("Quit without saving") {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Quit without saving pressed");
+        ui.close_current_popup();
+        send_quit_message(message_sender)?;
+    }
+    ui.same_line();
+    trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Cancel");
+    if ui.button("Cancel") {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Cancel pressed, not quitting");
+        ui.close_current_popup();
+    }
+
+    popup_token.end();
+    Ok(())
+}
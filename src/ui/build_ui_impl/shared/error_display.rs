@@ -1,50 +1,232 @@
+use crate::config::compile_time::ui_config::MAX_ERROR_HISTORY;
 use crate::config::read_config_value;
 use crate::config::run_time::ui_config::theme::Theme;
 use crate::helper;
+use crate::helper::file_helper::open_file_in_editor;
 use crate::helper::logging::event_targets::*;
+use crate::helper::rng::new_rng;
+use crate::resources::resource_manager::{resource_folder_path, ResourceKind};
+use crate::ui::build_ui_impl::shared::explain_mode::explain_item;
 use crate::ui::build_ui_impl::shared::constants::{MISSING_VALUE_TEXT, NO_VALUE_TEXT, UNKNOWN_VALUE_TEXT};
+use crate::ui::clipboard_integration::clipboard_available;
 use crate::ui::build_ui_impl::shared::{display_c_const_pointer, display_c_mut_pointer, display_maybe_c_mut_pointer, tree_utils};
+use crate::FallibleFn;
 use backtrace::{BacktraceFrame, BacktraceSymbol};
-use color_eyre::section::Section;
-use color_eyre::section::SectionExt;
-use color_eyre::Report;
-use fancy_regex::*;
+use color_eyre::eyre::WrapErr;
+use color_eyre::{eyre, Help, Report};
 use helper::logging::*;
-use imgui::{Condition, TableFlags, TreeNodeId, Ui};
+use imgui::{Condition, ItemHoveredFlags, StyleColor, TableFlags, TreeNodeId, Ui};
 use indoc::indoc;
 use itertools::Itertools;
 use lazy_static::lazy_static;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use std::collections::HashMap;
 use std::ffi::c_void;
-use std::sync::atomic::AtomicBool;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
 use std::sync::atomic::Ordering::Relaxed;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::field::Empty;
 use tracing::{trace, trace_span, warn, Metadata, debug};
 use tracing_error::SpanTraceStatus;
 
 lazy_static! {
-    /// Vector of errors we are currently displaying
-    static ref ERRORS: Mutex<Vec<Report>> = Mutex::new(Vec::default());
+    /// The errors we are currently displaying
+    static ref ERRORS: Mutex<ErrorList> = Mutex::new(ErrorList::default());
+    /// History of all the errors that have occurred (even ones that were dismissed), oldest first
+    static ref ERROR_HISTORY: Mutex<Vec<ErrorHistoryEntry>> = Mutex::new(Vec::default());
 }
 /// Atomic (because it's static) boolean
 static SHOW_ERRORS_POPUP: AtomicBool = AtomicBool::new(false);
+/// Counter used to give each [DisplayedError] a unique ID
+static NEXT_ERROR_ID: AtomicUsize = AtomicUsize::new(0);
+/// An error that is stored to be displayed in the errors popup
+struct DisplayedError {
+    /// The report to display. Shared with the [ERROR_HISTORY]
+    report: Arc<Report>,
+    /// The report, formatted without ANSI codes. Used to check whether a new report is a duplicate of this one
+    formatted: String,
+    /// How many times this (identical) error has occurred
+    count: usize,
+    /// When this error first occurred
+    first_occurred: SystemTime,
+    /// When this error last occurred
+    last_occurred: SystemTime,
+    /// Unique ID for this error, so the tab keeps the same [imgui] ID even when the title changes
+    id: usize,
+    /// Text the user typed to filter the backtrace frames and spans in this error's tab
+    filter: String,
+    /// Where this error's report was last saved to (see [save_report_to_file]), so it can be shown to the user
+    saved_path: Option<PathBuf>,
+}
+
+/// The errors displayed in the errors popup, capped to a maximum number
+///
+/// When there are too many, the oldest errors are evicted first, and counted in [dropped](ErrorList::dropped) so the user knows they missed some
+#[derive(Default)]
+struct ErrorList {
+    /// The errors, oldest first
+    errors: Vec<DisplayedError>,
+    /// How many errors have been evicted because there were too many. Reset when the list is emptied
+    dropped: usize,
+}
+
+impl ErrorList {
+    /// Adds an error to the list, returning the (shared) report that ended up being stored
+    ///
+    /// If an identical error (same `formatted` text) is already in the list, that error's count is incremented instead of adding a duplicate.
+    /// Otherwise, the oldest errors are evicted until there are at most `max_errors`
+    fn add(&mut self, report: Report, formatted: String, max_errors: usize) -> Arc<Report> {
+        let now = SystemTime::now();
+        if let Some(existing) = self.errors.iter_mut().find(|existing| existing.formatted == formatted) {
+            existing.count += 1;
+            existing.last_occurred = now;
+            trace!(target: UI_DEBUG_GENERAL, count = existing.count, "error was a duplicate, incremented count");
+            return Arc::clone(&existing.report);
+        }
+
+        let report = Arc::new(report);
+        self.errors.push(DisplayedError {
+            report: Arc::clone(&report),
+            formatted,
+            count: 1,
+            first_occurred: now,
+            last_occurred: now,
+            id: NEXT_ERROR_ID.fetch_add(1, Relaxed),
+            filter: String::new(),
+            saved_path: None,
+        });
+        // Always keep the error that just happened, even if the max is 0
+        let max_errors = max_errors.max(1);
+        if self.errors.len() > max_errors {
+            let excess = self.errors.len() - max_errors;
+            debug!(target: UI_DEBUG_GENERAL, excess, "too many errors stored, removing oldest");
+            self.errors.drain(0..excess);
+            self.dropped += excess;
+        }
+        report
+    }
+
+    /// Removes all the errors, and resets the [dropped](ErrorList::dropped) count since they aren't relevant anymore
+    fn clear(&mut self) {
+        self.errors.clear();
+        self.dropped = 0;
+    }
+}
+
+/// An error that was recorded in the [ERROR_HISTORY]
+struct ErrorHistoryEntry {
+    /// When the error occurred
+    time: Instant,
+    report: Arc<Report>,
+    /// Unique ID for this entry, used for the [imgui] ID of the tree node
+    id: usize,
+}
 
 /// Call this function whenever an error occurs (only call once) and you want to display the error
+///
+/// If an identical error (same formatted text) is already being displayed, that error's count is incremented instead of adding a duplicate
 pub fn an_error_occurred(report: Report) {
     debug!(target: GENERAL_WARNING_NON_FATAL, "received error to display in ui: {report:#}");
-    let mut errors_vec = match ERRORS.lock() {
+    let formatted = format_report_string_no_ansi(&report);
+    let max_errors = read_config_value(|config| config.runtime.ui.max_displayed_errors);
+    let mut error_list = match ERRORS.lock() {
+        Ok(lock) => lock,
+        Err(err) => {
+            warn!(target: GENERAL_WARNING_NON_FATAL, "errors list mutex was poisoned by some other thread");
+            err.into_inner()
+        }
+    };
+    let report = error_list.add(report, formatted, max_errors);
+    drop(error_list);
+
+    let mut history = match ERROR_HISTORY.lock() {
         Ok(lock) => lock,
         Err(err) => {
-            warn!(target: GENERAL_WARNING_NON_FATAL, "errors Vec mutex was poisoned by some other thread");
+            warn!(target: GENERAL_WARNING_NON_FATAL, "error history mutex was poisoned by some other thread");
             err.into_inner()
         }
     };
-    errors_vec.push(report);
+    history.push(ErrorHistoryEntry {
+        time: Instant::now(),
+        report,
+        id: NEXT_ERROR_ID.fetch_add(1, Relaxed),
+    });
+    if history.len() > MAX_ERROR_HISTORY {
+        let excess = history.len() - MAX_ERROR_HISTORY;
+        history.drain(0..excess);
+    }
+    drop(history);
+
     SHOW_ERRORS_POPUP.store(true, Relaxed);
 }
 
+/// Gets the message of the most recent error in the error history, and when it occurred
+pub fn last_error() -> Option<(String, Instant)> {
+    let history = match ERROR_HISTORY.lock() {
+        Ok(lock) => lock,
+        Err(err) => {
+            warn!(target: GENERAL_WARNING_NON_FATAL, "error history mutex was poisoned by some other thread");
+            err.into_inner()
+        }
+    };
+    history.last().map(|entry| (entry.report.chain().next().map_or(UNKNOWN_VALUE_TEXT.to_string(), |err| err.to_string()), entry.time))
+}
+
+/// Renders the contents of the error history window
+///
+/// Lists every error that has occurred (newest first), with how long ago it happened. Expanding an entry shows the full report
+pub fn render_error_history_window(ui: &Ui, visible: bool) -> FallibleFn {
+    let span_render_error_history = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "render_error_history").entered();
+    if !visible {
+        trace!(target: UI_TRACE_BUILD_INTERFACE, "not visible");
+        return Ok(());
+    }
+    let colours = read_config_value(|config| config.runtime.ui.colours);
+    let mut history = match ERROR_HISTORY.lock() {
+        Ok(lock) => lock,
+        Err(err) => {
+            warn!(target: GENERAL_WARNING_NON_FATAL, "error history mutex was poisoned by some other thread");
+            err.into_inner()
+        }
+    };
+
+    trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Clear History");
+    if ui.button("Clear History") {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Clear History pressed");
+        history.clear();
+    }
+    ui.separator();
+
+    if history.is_empty() {
+        ui.text_colored(colours.value.missing_value, "No errors have occurred");
+    }
+    for entry in history.iter().rev() {
+        let maybe_tree_node = tree_utils::tree_node_with_custom_text(ui, &format!("error_history_{}", entry.id));
+        // Round to seconds, or the display changes every frame and is unreadable
+        let ago = Duration::from_secs(entry.time.elapsed().as_secs());
+        ui.text_colored(colours.value.number, format!("{} ago", humantime::format_duration(ago)));
+        ui.same_line_with_spacing(0.0, 0.0);
+        ui.text_colored(colours.value.symbol, ":\t");
+        ui.same_line_with_spacing(0.0, 0.0);
+        ui.text_colored(
+            colours.value.error_message,
+            entry.report.chain().next().map_or(UNKNOWN_VALUE_TEXT.to_string(), |err| err.to_string()),
+        );
+
+        if let Some(tree_node) = maybe_tree_node {
+            display_eyre_report(ui, &entry.report, "");
+            tree_node.end();
+        }
+    }
+
+    span_render_error_history.exit();
+    Ok(())
+}
+
 pub fn render_errors_popup(ui: &Ui) {
     const MODAL_NAME: &str = "Error(s)";
 
@@ -68,21 +250,25 @@ pub fn render_errors_popup(ui: &Ui) {
             }
             Some(token) => token,
         };
-        let mut errors_vec = match ERRORS.lock() {
+        let mut error_list = match ERRORS.lock() {
             Ok(lock) => lock,
             Err(err) => {
-                warn!(target: GENERAL_WARNING_NON_FATAL, "errors Vec mutex was poisoned by some other thread");
+                warn!(target: GENERAL_WARNING_NON_FATAL, "errors list mutex was poisoned by some other thread");
                 err.into_inner()
             }
         };
         let colours = read_config_value(|config| config.runtime.ui.colours);
 
-        if errors_vec.is_empty() {
+        if error_list.errors.is_empty() {
             trace!(target: UI_TRACE_BUILD_INTERFACE, "errors modal: visible but empty");
+            // All the errors have been closed, so the dropped ones aren't relevant anymore
+            error_list.clear();
             ui.text_colored(colours.text.normal, "No errors to display!\nYou can safely close this window");
             // Here's a little egg for easter I put in here
-            let random_chars = (0..=thread_rng().gen_range(12usize..=20usize)) //Generates a random range of 12 to 20 elements
-                .map(|_| thread_rng().gen_range('\u{0021}'..='\u{00FF}'))
+            // Uses the app's RNG so it's the same every frame when there's a seed set, which keeps screenshots reproducible
+            let mut rng = new_rng();
+            let random_chars = (0..=rng.gen_range(12usize..=20usize)) //Generates a random range of 12 to 20 elements
+                .map(|_| rng.gen_range('\u{0021}'..='\u{00FF}'))
                 .join(""); //Maps each element to a random char in a reasonable range of unicode chars
             ui.text_colored(
                 [0.5, 0.5, 0.5, 0.02 /*Almost invisible*/],
@@ -105,20 +291,59 @@ pub fn render_errors_popup(ui: &Ui) {
             return;
         }
 
+        trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Copy All");
+        if clipboard_button(ui, "Copy All", "Copies the full text of every error in this popup to the clipboard") {
+            let all = error_list.errors.iter().map(|error| error.formatted.as_str()).join(&format!("\n{}\n", "=".repeat(80)));
+            ui.set_clipboard_text(all);
+        }
+        ui.same_line();
+        trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Dismiss All");
+        if ui.button("Dismiss All") {
+            debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Dismiss All pressed");
+            error_list.clear();
+        }
+        explain_item(ui, "Closes every error in this popup. They're still kept in the error history window");
+
+        if error_list.dropped > 0 {
+            ui.text_colored(colours.severity.warning, format!("{} older errors dropped (see the error history)", error_list.dropped));
+        }
+
         if let Some(tab_bar_token) = ui.tab_bar("Error tab bar") {
             trace!(target: UI_TRACE_BUILD_INTERFACE, "error tab bar visible");
-            errors_vec.retain(|report| {
+            error_list.errors.retain_mut(|error| {
+                let report = &error.report;
                 let span_error_tabs = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "error_tabs", report = format_report_display(report), opened = Empty).entered();
                 // This bool is passed into [imgui] when creating each tab, so [imgui] will set it to [false] when the user closes the tab
                 // Since we're inside [retain_mut()], we can use this to decide which reports to keep, since it'll only be false once the user closes it
                 let mut opened = true;
-                let title = format!(
+                let mut title = format!(
                     "{}",
                     report.chain().next().expect("Every error should have at least one error in the chain, but `.next()` returned [None]")
                 );
-                if let Some(tab) = ui.tab_item_with_opened(&title, &mut opened) {
+                if error.count > 1 {
+                    title += &format!(" (×{})", error.count);
+                }
+                // Everything after the `###` is used for the ID, so that the tab doesn't change identity when the count changes
+                let label = format!("{title}###error_tab_{}", error.id);
+                let maybe_tab = ui.tab_item_with_opened(&label, &mut opened);
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(if error.count > 1 {
+                        format!("First occurred {}\nLast occurred {}", format_error_time(error.first_occurred), format_error_time(error.last_occurred))
+                    } else {
+                        format!("Occurred {}", format_error_time(error.last_occurred))
+                    });
+                }
+                if let Some(tab) = maybe_tab {
                     trace!(target: UI_TRACE_BUILD_INTERFACE, "error tab {title} selected");
-                    display_eyre_report(ui, report);
+                    display_report_actions(ui, report, &mut error.saved_path);
+                    trace!(target: UI_TRACE_BUILD_INTERFACE, filter = error.filter, "[Input] Filter");
+                    if ui.input_text("Filter", &mut error.filter).hint("symbol name or file path").build() {
+                        debug!(target: UI_DEBUG_USER_INTERACTION, filter = error.filter, "[Input] Filter changed");
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Only shows the backtrace frames and spans whose name or file path contain this text (case-insensitive)");
+                    }
+                    display_eyre_report(ui, report, &error.filter);
                     tab.end();
                 } else {
                     trace!(target: UI_TRACE_BUILD_INTERFACE, "error tab {title} not selected");
@@ -140,10 +365,178 @@ pub fn render_errors_popup(ui: &Ui) {
     });
 }
 
+/// Formats when an error occurred as a timestamp, along with how long ago that was
+fn format_error_time(time: SystemTime) -> String {
+    // Round to seconds, or the display changes every frame and is unreadable
+    let ago = Duration::from_secs(time.elapsed().unwrap_or_default().as_secs());
+    format!("{} ({} ago)", humantime::format_rfc3339_seconds(time), humantime::format_duration(ago))
+}
+
+/// Displays a button for copying something to the clipboard, returning whether it was pressed
+///
+/// If the clipboard couldn't be initialised, the button is disabled and the tooltip says why, instead of silently doing nothing
+pub fn clipboard_button(ui: &Ui, label: &str, tooltip: &str) -> bool {
+    let clipboard_available = clipboard_available();
+    let disabled_token = ui.begin_disabled(!clipboard_available);
+    // This goes through the clipboard backend we gave to imgui, which logs a warning by itself if it fails
+    let pressed = ui.button(label);
+    disabled_token.end();
+    if pressed {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] {label} pressed");
+    }
+    if ui.is_item_hovered_with_flags(ItemHoveredFlags::ALLOW_WHEN_DISABLED) {
+        if clipboard_available {
+            ui.tooltip_text(tooltip);
+        } else {
+            ui.tooltip(|| ui.text_colored(read_config_value(|config| config.runtime.ui.colours.severity.warning), "The clipboard isn't available (it couldn't be initialised)"));
+        }
+    }
+    pressed
+}
+
+/// Displays the buttons that let the user do something with a report (copy it, jump to the source, etc)
+fn display_report_actions(ui: &Ui, report: &Report, saved_path: &mut Option<PathBuf>) {
+    let span_report_actions = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "report_actions").entered();
+
+    trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Copy");
+    if clipboard_button(ui, "Copy", "Copies the full text of this error to the clipboard") {
+        ui.set_clipboard_text(format_report_string_no_ansi(report));
+    }
+
+    ui.same_line();
+    let location = find_report_source_location(report);
+    trace!(target: UI_TRACE_BUILD_INTERFACE, ?location, "[Button] Open source location");
+    let disabled_token = ui.begin_disabled(location.is_none());
+    if ui.button("Open source location") {
+        debug!(target: UI_DEBUG_USER_INTERACTION, ?location, "[Button] Open source location pressed");
+        if let Some((file, line)) = &location {
+            if let Err(report) = open_file_in_editor(file, Some(*line)) {
+                let report = report.wrap_err("could not open source location of error");
+                warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report));
+            }
+        }
+    }
+    disabled_token.end();
+    if ui.is_item_hovered_with_flags(ItemHoveredFlags::ALLOW_WHEN_DISABLED) {
+        match &location {
+            Some((file, line)) => ui.tooltip_text(format!("Opens {}:{line} in $EDITOR (or the OS default program)", file.display())),
+            None => ui.tooltip_text("This error's backtrace doesn't have any frames with a known file and line"),
+        }
+    }
+
+    ui.same_line();
+    trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Save report…");
+    if ui.button("Save report…") {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Save report… pressed");
+        // Don't use [an_error_occurred] here: if saving keeps failing, every attempt would add another error to the popup we're drawing
+        match save_report_to_file(report) {
+            Ok(path) => {
+                debug!(target: UI_DEBUG_GENERAL, ?path, "saved error report");
+                *saved_path = Some(path);
+            }
+            Err(report) => {
+                let report = report.wrap_err("could not save error report");
+                warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report));
+            }
+        }
+    }
+    if ui.is_item_hovered() {
+        ui.tooltip_text("Saves the full report (chain, backtrace and span trace) to a text file in the crash reports folder");
+    }
+    if let Some(path) = saved_path {
+        let colours = read_config_value(|config| config.runtime.ui.colours);
+        ui.text_colored(colours.value.value_label, "Saved to: ");
+        ui.same_line_with_spacing(0.0, 0.0);
+        ui.text_colored(colours.value.file_location, path.display().to_string());
+    }
+
+    span_report_actions.exit();
+}
+
+/// Saves the full text of a report to a timestamped file in the crash reports folder, returning the path of the file
+///
+/// The file contains the formatted report (without ANSI codes), followed by the same chain, backtrace and span trace that [display_eyre_report] shows
+fn save_report_to_file(report: &Report) -> eyre::Result<PathBuf> {
+    let folder = resource_folder_path(ResourceKind::CrashReports)?;
+    fs::create_dir_all(&folder)
+        .wrap_err("could not create crash reports folder")
+        .with_note(|| format!("crash reports folder: {}", folder.display()))?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let path = folder.join(format!("error_report_{}_{:03}.txt", timestamp.as_secs(), timestamp.subsec_millis()));
+
+    // Writing to a [String] never fails, so the results can be ignored
+    let mut text = format_report_string_no_ansi(report);
+    text += "\n\n===== Chain =====\n";
+    for (index, error) in report.chain().enumerate() {
+        let _ = writeln!(text, "{index:>2}: {error}");
+    }
+
+    text += "\n===== Backtrace =====\n";
+    match report.handler().downcast_ref::<color_eyre::Handler>().and_then(color_eyre::Handler::backtrace) {
+        None => text += "<no backtrace>\n",
+        Some(backtrace) => {
+            for (index, frame) in backtrace.frames().iter().enumerate() {
+                if frame.symbols().is_empty() {
+                    let _ = writeln!(text, "{index:>2}: {:?} <Unresolved>", frame.ip());
+                }
+                for (sub_index, symbol) in frame.symbols().iter().enumerate() {
+                    let name = symbol.name().map_or(UNKNOWN_VALUE_TEXT.to_string(), |name| name.to_string());
+                    let _ = writeln!(text, "{index:>2}.{sub_index}: {name}");
+                    if let Some(file) = symbol.filename() {
+                        let _ = writeln!(text, "\tat {}:{}", file.display(), symbol.lineno().map_or(UNKNOWN_VALUE_TEXT.to_string(), |line| line.to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    text += "\n===== Span trace =====\n";
+    match report.handler().downcast_ref::<color_eyre::Handler>().and_then(color_eyre::Handler::span_trace) {
+        None => text += "<no span trace>\n",
+        Some(span_trace) => {
+            let mut depth = 0;
+            span_trace.with_spans(|metadata, formatted_span_fields| {
+                let _ = writeln!(text, "{depth:>2}: {}::{} {{{formatted_span_fields}}}", metadata.target(), metadata.name());
+                if let Some(file) = metadata.file() {
+                    let _ = writeln!(text, "\tat {file}:{}", metadata.line().map_or(UNKNOWN_VALUE_TEXT.to_string(), |line| line.to_string()));
+                }
+                depth += 1;
+                true
+            });
+        }
+    }
+
+    fs::write(&path, text)
+        .wrap_err("could not write error report to file")
+        .with_note(|| format!("file path: {}", path.display()))?;
+    Ok(path)
+}
+
+/// Finds the file and line of the top-most backtrace frame in the report that has a known location
+///
+/// Frames from our own crate are preferred, since the top of the backtrace is usually full of [eyre]/[backtrace] internals
+fn find_report_source_location(report: &Report) -> Option<(PathBuf, u32)> {
+    let backtrace = report.handler().downcast_ref::<color_eyre::Handler>()?.backtrace()?;
+    let mut first_known = None;
+    for symbol in backtrace.frames().iter().flat_map(BacktraceFrame::symbols) {
+        let (file, line) = match (symbol.filename(), symbol.lineno()) {
+            (Some(file), Some(line)) => (file, line),
+            _ => continue,
+        };
+        let is_ours = symbol.name().map_or(false, |name| name.to_string().starts_with(concat!(env!("CARGO_CRATE_NAME"), "::")));
+        if is_ours {
+            return Some((file.to_path_buf(), line));
+        }
+        first_known.get_or_insert_with(|| (file.to_path_buf(), line));
+    }
+    first_known
+}
+
 /// Function that displays an [eyre::Report] in [imgui]
 ///
 /// This doesn't create any windows or popups, just renders the error information.
-pub fn display_eyre_report(ui: &Ui, report: &Report) {
+/// If `filter` isn't empty, only the backtrace frames and spans whose name or file path contain the `filter` (case-insensitive) are shown
+pub fn display_eyre_report(ui: &Ui, report: &Report, filter: &str) {
     /*
     A note on how I've structured this:
     A lot of the UI code requires lots of `unwrap()`s or lots of `if/else`s, which means it gets quite heavily nested
@@ -155,6 +548,7 @@ pub fn display_eyre_report(ui: &Ui, report: &Report) {
 
     let span_display_error_report = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "display_error_report").entered();
     let colours = read_config_value(|config| config.runtime.ui.colours);
+    let filter = filter.to_lowercase();
     macro_rules! section {
         ($title:literal, $body:expr) => {{
             let span_section = trace_span!(target: UI_TRACE_BUILD_INTERFACE, $title).entered();
@@ -171,29 +565,66 @@ pub fn display_eyre_report(ui: &Ui, report: &Report) {
         }};
     }
     section!("Chain", {
-        for err in report.chain() {
-            // We don't use the alternate specifier since we just want the single error, not sub-errors
-            let err_string = err.to_string();
-            trace!(target: UI_TRACE_BUILD_INTERFACE, "[Bullet] {}", err_string);
-            ui.bullet();
-            ui.same_line();
-            ui.text_colored(colours.value.error_message, err_string);
-        }
+        let chain = report.chain().collect_vec();
+        display_error_chain(ui, &colours, &chain, 0);
     });
 
-    section!("Backtrace", display_backtrace(ui, &colours, report));
-    section!("Span trace", display_span_trace(ui, &colours, report));
+    section!("Backtrace", display_backtrace(ui, &colours, report, &filter));
+    section!("Span trace", display_span_trace(ui, &colours, report, &filter));
     //TODO: Report sections
     span_display_error_report.exit();
 }
 
+/// Recursively displays the chain of errors in a report as a nested tree
+///
+/// The outermost error is at the top, and each error's cause is shown as a child of that error's node, so that the causality is visible
+fn display_error_chain(ui: &Ui, colours: &Theme, chain: &[&(dyn std::error::Error + 'static)], depth: usize) {
+    let (err, causes) = match chain.split_first() {
+        None => return,
+        Some(split) => split,
+    };
+    // We don't use the alternate specifier since we just want the single error, not sub-errors
+    let err_string = err.to_string();
+
+    // The root cause doesn't have any children, so it doesn't need to be a node that can be expanded
+    if causes.is_empty() {
+        trace!(target: UI_TRACE_BUILD_INTERFACE, depth, "[Bullet] {}", err_string);
+        ui.bullet();
+        ui.same_line();
+        ui.text_colored(colours.value.error_message, err_string);
+        return;
+    }
+
+    trace!(target: UI_TRACE_BUILD_INTERFACE, depth, "[Node] {}", err_string);
+    let text_colour_token = ui.push_style_color(StyleColor::Text, colours.value.error_message);
+    // Depth is used as the ID, since the error messages aren't guaranteed to be unique
+    let maybe_node = ui
+        .tree_node_config(TreeNodeId::Str(format!("error_chain_{depth}")))
+        .label::<&str, &str>(&err_string)
+        .opened(true, Condition::FirstUseEver)
+        .push();
+    text_colour_token.pop();
+
+    if let Some(node) = maybe_node {
+        display_error_chain(ui, colours, causes, depth + 1);
+        node.end();
+    }
+}
+
+/// Checks whether any of the `candidates` contain the `filter`, ignoring case
+///
+/// Always true when the `filter` is empty. The `filter` should already be lowercase
+fn passes_filter(filter: &str, candidates: &[Option<&str>]) -> bool {
+    filter.is_empty() || candidates.iter().flatten().any(|candidate| candidate.to_lowercase().contains(filter))
+}
+
 // ===== BACK TRACE =====
 // TODO: Add some tooltips that explain the subtleties and meanings of the backtrace
 //  For example, why compressed frames have "outer" prefixing the IP, module addr, and symbol addr,
 //  What compressed frames are
 //  What unresolved/empty frames are
 //  What each of the symbols etc means
-fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report) {
+fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report, filter: &str) {
     let handler = match report.handler().downcast_ref::<color_eyre::Handler>() {
         // Couldn't downcast to get the handler
         None => {
@@ -229,9 +660,11 @@ fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report) {
         This means that normal singular frames should be fine
          */
         match frame.symbols().len() {
+            // Empty frames don't have a name or a file, so they can never match a filter
+            0 if !filter.is_empty() => (),
             0 => display_empty_frame(ui, colours, index, frame),
-            1 => display_single_frame(ui, colours, index, frame),
-            _ => display_compressed_frame(ui, colours, index, frame),
+            1 => display_single_frame(ui, colours, index, frame, filter),
+            _ => display_compressed_frame(ui, colours, index, frame, filter),
         }
     }
 
@@ -302,7 +735,7 @@ fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report) {
         tree_node.end();
     }
 
-    fn display_single_frame(ui: &Ui, colours: &Theme, index: usize, frame: &BacktraceFrame) {
+    fn display_single_frame(ui: &Ui, colours: &Theme, index: usize, frame: &BacktraceFrame, filter: &str) {
         let frame_instruction_pointer: *mut c_void = frame.ip();
         let frame_symbol_address: *mut c_void = frame.symbol_address();
         let frame_module_base_address: Option<*mut c_void> = frame.module_base_address();
@@ -316,6 +749,7 @@ fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report) {
             frame_instruction_pointer,
             frame_symbol_address,
             frame_module_base_address,
+            filter,
         );
     }
 
@@ -326,18 +760,28 @@ fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report) {
     /// > of functions are inlined into one frame then multiple symbols will be
     /// > returned. The first symbol listed is the "innermost function", whereas
     /// > the last symbol is the outermost (last caller).
-    fn display_compressed_frame(ui: &Ui, colours: &Theme, frame_index: usize, frame: &BacktraceFrame) {
+    fn display_compressed_frame(ui: &Ui, colours: &Theme, frame_index: usize, frame: &BacktraceFrame, filter: &str) {
         let frame_instruction_pointer: *mut c_void = frame.ip();
         let frame_symbol_address: *mut c_void = frame.symbol_address();
         let frame_module_base_address: Option<*mut c_void> = frame.module_base_address();
 
         for (sub_frame_index, symbol) in frame.symbols().iter().enumerate() {
             let frame_index_str = format!("{frame_index:>2}.{sub_frame_index}");
-            display_symbol_frame(ui, colours, &frame_index_str, symbol, frame_instruction_pointer, frame_symbol_address, frame_module_base_address);
+            display_symbol_frame(
+                ui,
+                colours,
+                &frame_index_str,
+                symbol,
+                frame_instruction_pointer,
+                frame_symbol_address,
+                frame_module_base_address,
+                filter,
+            );
         }
     }
 
     /// The shared function called by
+    #[allow(clippy::too_many_arguments)] // Most of these are just the frame's info, which is shared between the symbols of a compressed frame
     fn display_symbol_frame(
         ui: &Ui,
         colours: &Theme,
@@ -346,7 +790,15 @@ fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report) {
         frame_instruction_pointer: *mut c_void,
         frame_symbol_address: *mut c_void,
         frame_module_base_address: Option<*mut c_void>,
+        filter: &str,
     ) {
+        let demangled_name = symbol.name().map(|name| name.to_string());
+        let file_path = symbol.filename().map(|file| file.display().to_string());
+        if !passes_filter(filter, &[demangled_name.as_deref(), file_path.as_deref()]) {
+            trace!(target: UI_TRACE_BUILD_INTERFACE, frame_index_str, "frame filtered out");
+            return;
+        }
+
         let maybe_tree_node = tree_utils::tree_node_with_custom_text(
             ui,
             TreeNodeId::<&str>::Ptr(symbol as *const BacktraceSymbol as *const c_void), // Use the BacktraceSymbol for the node's ID
@@ -363,65 +815,8 @@ fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report) {
         ui.text_colored(colours.value.symbol, " - ");
         ui.same_line_with_spacing(0.0, 0.0);
         // Demangled symbol name in title
-        if let Some(ref symbol_name) = symbol.name() {
-            let full = symbol_name.to_string(); // Use to_string() for demangled name
-            let mut short = String::with_capacity(128);
-            let mut generic_depth: u64 = 0; // u8 since we should never go >256 and it should always be +ve, -ve is an error
-                                            // Here we process the symbol name to make it easier to read
-                                            // Remove the generic type arguments
-                                            // And any `::impl$XXX::` parts
-            let mut index: usize = 0;
-            'shorten: loop {
-                let segment = &full[index..];
-                if index >= full.len() {
-                    // Got to the end of the string
-                    break 'shorten;
-                }
-
-                let char = match &segment.chars().next() {
-                    None => break warn!(target: GENERAL_WARNING_NON_FATAL, full, short, segment, index, "didn't have a char at index {index}"),
-                    Some(c) => *c,
-                };
-
-                if char == '<' {
-                    generic_depth += 1;
-                    index += 1;
-                    continue;
-                }
-                if char == '>' {
-                    generic_depth -= 1;
-                    index += 1;
-                    continue;
-                }
-
-                if generic_depth != 0 {
-                    index += 1;
-                    continue;
-                }
-
-                if segment.starts_with("::impl$") {
-                    // Find where the next part of the path starts
-                    // By skipping to the next colon
-                    index += 7; // Skip the chars of `::impl$`
-                    let next_colon_index = match full[index..].find(':') {
-                        None => {
-                            break warn!(
-                                target: GENERAL_WARNING_NON_FATAL,
-                                full, short, segment, index, "didn't find next colon - symbol path seems to end with impl block. this shouldn't happen"
-                            )
-                        }
-                        Some(idx) => index + idx,
-                    };
-                    index = next_colon_index;
-                    continue 'shorten;
-                }
-
-                //If we get here, we're skipped all the unnecessary chars, so add this ones
-                short.push(char);
-                index += 1;
-            }
-
-            ui.text_colored(colours.value.function_name, short);
+        if let Some(ref full) = demangled_name {
+            ui.text_colored(colours.value.function_name, shorten_symbol_name(full));
         } else {
             ui.text_colored(colours.severity.warning, UNKNOWN_VALUE_TEXT);
         }
@@ -532,8 +927,98 @@ fn display_backtrace(ui: &Ui, colours: &Theme, report: &Report) {
     }
 }
 
+/// Shortens a (demangled) symbol name to make it easier to read
+///
+/// Removes the generic type arguments, any `::impl$XXX::` parts, and the trailing hash, and turns closure markers into a compact `{closure}`.
+/// The full name is still shown in the frame's table
+fn shorten_symbol_name(full: &str) -> String {
+    let mut short = String::with_capacity(full.len());
+    let mut generic_depth: usize = 0;
+    // Always a byte index on a char boundary, since we either step over a whole char or over an ASCII pattern we matched
+    let mut index: usize = 0;
+    'shorten: while let Some(char) = full[index..].chars().next() {
+        let segment = &full[index..];
+        let next_index = index + char.len_utf8();
+
+        // Function pointer types (e.g. `<fn() -> T as Trait>::f`) have arrows, which don't close a generic
+        if segment.starts_with("->") {
+            if generic_depth == 0 {
+                short.push_str("->");
+            }
+            index += "->".len();
+            continue 'shorten;
+        }
+        if char == '<' {
+            generic_depth += 1;
+            index = next_index;
+            continue 'shorten;
+        }
+        if char == '>' {
+            // Every `>` should have a matching `<`, but don't panic if we get a symbol we don't understand
+            generic_depth = generic_depth.saturating_sub(1);
+            index = next_index;
+            continue 'shorten;
+        }
+
+        if generic_depth != 0 {
+            index = next_index;
+            continue 'shorten;
+        }
+
+        // Legacy mangling gives closures as `{{closure}}`, v0 mangling gives them as `{closure#0}`
+        // Both get turned into a more compact `{closure}`
+        if segment.starts_with("{{closure}}") {
+            short.push_str("{closure}");
+            index += "{{closure}}".len();
+            continue 'shorten;
+        }
+        if segment.starts_with("{closure#") {
+            short.push_str("{closure}");
+            index += match segment.find('}') {
+                None => {
+                    warn!(target: GENERAL_WARNING_NON_FATAL, full, short, segment, index, "closure marker wasn't closed");
+                    break 'shorten;
+                }
+                Some(idx) => idx + 1,
+            };
+            continue 'shorten;
+        }
+
+        // Legacy symbols end in a hash segment (`::h` followed by 16 hex digits), which is just noise for us
+        if let Some(hash) = segment.strip_prefix("::h") {
+            if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                break 'shorten;
+            }
+        }
+
+        if segment.starts_with("::impl$") {
+            // Find where the next part of the path starts
+            // By skipping to the next colon
+            index += "::impl$".len();
+            let next_colon_index = match full[index..].find(':') {
+                None => {
+                    warn!(
+                        target: GENERAL_WARNING_NON_FATAL,
+                        full, short, segment, index, "didn't find next colon - symbol path seems to end with impl block. this shouldn't happen"
+                    );
+                    break 'shorten;
+                }
+                Some(idx) => index + idx,
+            };
+            index = next_colon_index;
+            continue 'shorten;
+        }
+
+        //If we get here, we're skipped all the unnecessary chars, so add this ones
+        short.push(char);
+        index = next_index;
+    }
+
+    short
+}
+
 // ===== SPAN TRACE =====
-fn display_span_trace(ui: &Ui, colours: &Theme, report: &Report) {
+fn display_span_trace(ui: &Ui, colours: &Theme, report: &Report, filter: &str) {
     let handler = match report.handler().downcast_ref::<color_eyre::Handler>() {
         // Couldn't downcast to get the handler
         None => {
@@ -573,7 +1058,10 @@ fn display_span_trace(ui: &Ui, colours: &Theme, report: &Report) {
     // [with_spans] calls the closure on every span in the trace, as long as the closure returns `true`
     let mut depth = 0;
     span_trace.with_spans(|metadata: &'static Metadata<'static>, formatted_span_fields: &str| -> bool {
-        visit_each_span(ui, colours, metadata, formatted_span_fields, depth);
+        // Still increment the depth for filtered spans, so the displayed depths don't change when filtering
+        if passes_filter(filter, &[Some(metadata.name()), metadata.file()]) {
+            visit_each_span(ui, colours, metadata, formatted_span_fields, depth);
+        }
         depth += 1;
         true
     });
@@ -692,63 +1180,103 @@ fn parse_span_fields<'field>(formatted_span_fields: &'field str) -> HashMap<&'fi
     // So just in case, we have to account for that and use a Vec
     let mut field_map: HashMap<&'field str, Vec<&'field str>> = HashMap::new();
 
-    // Now we match our [Regex] (technically our [fancy_regex::Regex]) to the string, and extract the named captures
-    for maybe_capture in VALUE_EXTRACTOR_REGEX.captures_iter(formatted_span_fields) {
-        // Should give us 3 capture groups: (0) overall match, (1) key, (2) value
-        match maybe_capture {
-            Err(err) => {
-                warn!(
-                    target: GENERAL_WARNING_NON_FATAL,
-                    report = format_report_display(
-                        &Report::new(err)
-                            .wrap_err("encountered error when matching value extractor regex to formatted fields string")
-                            .section(VALUE_EXTRACTOR_REGEX.as_str().header("value extractor regex:"))
-                            .section(formatted_span_fields.to_owned().header("input string:"))
-                    )
-                );
+    /*
+    The fields are formatted as `key=value key=value ...`, but the values can contain pretty much anything (spaces, `=`, nested structs, strings etc)
+    So we can't just split on spaces or equals signs. Instead, we scan through the string and split it at the spaces that are followed by a `key=`,
+    but only if we aren't inside a string, char or some brackets (since `Foo { x=1 }` or `"a=b c=d"` are part of a single value)
+    */
+    for field in split_span_fields(formatted_span_fields) {
+        // Every field starts with a key (see [split_span_fields]), and keys never contain `=`, so the first `=` always separates the key and value
+        match field.split_once('=') {
+            Some((key, value)) if starts_with_field_key(field) => field_map.entry(key).or_default().push(value),
+            _ => warn!(
+                target: GENERAL_WARNING_NON_FATAL,
+                field,
+                formatted_span_fields,
+                "could not parse span field: field did not start with a `key=`"
+            ),
+        }
+    }
+
+    field_map
+}
+
+/// Splits a formatted span fields string into the individual `key=value` fields
+///
+/// Splits only happen on spaces that are directly followed by a `key=`, and are not inside a quoted string, char literal, or any brackets (`()`, `[]`, `{}`)
+fn split_span_fields(formatted_span_fields: &str) -> Vec<&str> {
+    let mut fields = vec![];
+    let mut field_start = 0;
+    // Index of the end of the char literal we're skipping over (if any), so quotes and brackets inside it (e.g. `'"'`) are ignored
+    let mut skip_until = 0;
+    // How many brackets deep we are
+    let mut depth: usize = 0;
+    let mut in_string = false;
+    // Whether the previous char was a backslash inside a string (so the current char is escaped)
+    let mut escaped = false;
+
+    for (index, char) in formatted_span_fields.char_indices() {
+        if index < skip_until {
+            continue;
+        }
+        if in_string {
+            match char {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
             }
-            Ok(capture /*should be called `match`*/) => {
-                let key = match capture.name("key") {
-                    None => {
-                        warn!(
-                            target: GENERAL_WARNING_NON_FATAL,
-                            "cannot have a match for <field> without also having a match for <key>: `capture.name(\"key\")` returned [None]"
-                        );
-                        continue;
-                    }
-                    Some(key) => key.as_str(),
-                };
-                let value = match capture.name("value") {
-                    None => {
-                        warn!(
-                            target: GENERAL_WARNING_NON_FATAL,
-                            "cannot have a match for <field> without also having a match for <value>: `capture.name(\"value\")` returned [None]"
-                        );
-                        continue;
-                    }
-                    Some(value) => value.as_str(),
-                };
-                field_map.entry(key).or_default().push(value);
+            continue;
+        }
+        match char {
+            '"' => in_string = true,
+            // Might also be a lifetime (e.g. in a type name), in which case it's just a normal char
+            '\'' => {
+                if let Some(len) = char_literal_len(&formatted_span_fields[index..]) {
+                    skip_until = index + len;
+                }
+            }
+            '(' | '[' | '{' => depth += 1,
+            // Saturating so unbalanced brackets don't underflow, we just do our best in that case
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ' ' if depth == 0 && starts_with_field_key(&formatted_span_fields[index + 1..]) => {
+                fields.push(&formatted_span_fields[field_start..index]);
+                field_start = index + 1;
             }
+            _ => (),
         }
     }
+    if field_start < formatted_span_fields.len() {
+        fields.push(&formatted_span_fields[field_start..]);
+    }
 
-    return field_map;
+    fields
+}
 
-    lazy_static! {
-        // Mostly working but will have to manually split string?: https://regex101.com/r/KCn0Q1/1
-        static ref VALUE_EXTRACTOR_REGEX: Regex = Regex::new(indoc::indoc! {r#"
-            (?P<field>(?#
-            Each field is made up of a key, an equals sign, and a value
-            The key is always a single word, underscores allowed - has to be valid rust identifier
-            The value can be pretty much any value, including spaces and symbols. Assume that it won't include equals sign, or it gets too tricky to compute
-            )(?P<key>(r#)?\w+)=(?P<value>[^=]*?))(?#
-            Now we do a positive lookahead to separate the next fields from this field
-            Each match *MUST* be followed by either another field, or the end of the string.
-            This makes it much easier to match
-            Here we repeat <field>, since can't use subroutines/expression references in this dialect of regex
-            )(?:$|(?: (?=(?:r#)?\w+=[^=]*?)))"#}).expect("Compile-time regex should be correct");
-    }
+/// If the string starts with a char literal (e.g. `'a'`, `'"'`, `'\''` or `'\u{1F600}'`), returns its length in bytes
+fn char_literal_len(string: &str) -> Option<usize> {
+    let inner = string.strip_prefix('\'')?;
+    let inner_len = match inner.strip_prefix('\\') {
+        Some(escape) => {
+            // The char straight after the backslash might be a quote (`\'`), but the rest of the escape never is (e.g. `u{1F600}` or `x7f`)
+            let escaped_char_len = escape.chars().next()?.len_utf8();
+            let rest_len = escape[escaped_char_len..].find('\'').filter(|&len| len <= "{10FFFF}".len())?;
+            // Plus one for the backslash
+            1 + escaped_char_len + rest_len
+        }
+        None => inner.chars().next()?.len_utf8(),
+    };
+    // Plus the two quotes
+    inner[inner_len..].starts_with('\'').then_some(inner_len + 2)
+}
+
+/// Checks if a string starts with a field key followed by an equals sign (`key=`)
+///
+/// The key is always a valid rust identifier (possibly a raw one with the `r#` prefix)
+fn starts_with_field_key(string: &str) -> bool {
+    let string = string.strip_prefix("r#").unwrap_or(string);
+    let key_len = string.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(string.len());
+    key_len > 0 && string[key_len..].starts_with('=')
 }
 
 fn display_span_fields<'field>(ui: &Ui, colours: &Theme, fields: Vec<ProcessedSpanField<'field>>) {
@@ -854,3 +1382,147 @@ fn process_span_fields<'field>(metadata: &'static Metadata<'static>, mut fields_
 
     fields
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn error_list_evicts_oldest() {
+        const OVERFILL: usize = 10;
+        let mut list = ErrorList::default();
+        for index in 0..MAX_ERROR_HISTORY + OVERFILL {
+            let message = format!("error {index}");
+            list.add(Report::msg(message.clone()), message, MAX_ERROR_HISTORY);
+        }
+
+        assert_eq!(list.errors.len(), MAX_ERROR_HISTORY);
+        assert_eq!(list.dropped, OVERFILL);
+        assert_eq!(list.errors.first().map(|error| error.formatted.as_str()), Some(format!("error {OVERFILL}").as_str()));
+        assert_eq!(
+            list.errors.last().map(|error| error.formatted.as_str()),
+            Some(format!("error {}", MAX_ERROR_HISTORY + OVERFILL - 1).as_str())
+        );
+
+        list.clear();
+        assert!(list.errors.is_empty());
+        assert_eq!(list.dropped, 0);
+    }
+
+    #[test]
+    fn error_list_duplicates_dont_evict() {
+        let mut list = ErrorList::default();
+        list.add(Report::msg("first"), "first".to_string(), 2);
+        list.add(Report::msg("second"), "second".to_string(), 2);
+        list.add(Report::msg("first"), "first".to_string(), 2);
+
+        assert_eq!(list.errors.iter().map(|error| (error.formatted.as_str(), error.count)).collect_vec(), vec![("first", 2), ("second", 1)]);
+        assert_eq!(list.dropped, 0);
+    }
+
+    #[test]
+    fn an_error_occurred_collapses_duplicates() {
+        // Only this test touches the global lists, but use a distinctive message so other errors can't be mistaken for ours
+        const MESSAGE: &str = "an_error_occurred_collapses_duplicates test error";
+        const REPEATS: usize = 5;
+        for _ in 0..REPEATS {
+            an_error_occurred(Report::msg(MESSAGE));
+        }
+
+        let errors = ERRORS.lock().unwrap();
+        let counts = errors.errors.iter().filter(|error| error.report.to_string() == MESSAGE).map(|error| error.count).collect_vec();
+        assert_eq!(counts, vec![REPEATS]);
+        let displayed_report = errors.errors.iter().find(|error| error.report.to_string() == MESSAGE).map(|error| Arc::clone(&error.report)).unwrap();
+        drop(errors);
+
+        // Every occurrence is still recorded in the history, sharing the one displayed report
+        let history = ERROR_HISTORY.lock().unwrap();
+        let history_entries = history.iter().filter(|entry| entry.report.to_string() == MESSAGE).collect_vec();
+        assert_eq!(history_entries.len(), REPEATS);
+        assert!(history_entries.iter().all(|entry| Arc::ptr_eq(&entry.report, &displayed_report)));
+    }
+
+    #[test]
+    fn shorten_symbol_strips_legacy_hash() {
+        assert_eq!(shorten_symbol_name("rust_ray::ui::build_ui_impl::build_ui::h0123456789abcdef"), "rust_ray::ui::build_ui_impl::build_ui");
+    }
+
+    #[test]
+    fn shorten_symbol_strips_generics() {
+        assert_eq!(
+            shorten_symbol_name("core::ops::function::FnOnce::call_once<void (*)(),tuple$<> >"),
+            "core::ops::function::FnOnce::call_once"
+        );
+    }
+
+    #[test]
+    fn shorten_symbol_compacts_closures() {
+        assert_eq!(shorten_symbol_name("rust_ray::program::run::{{closure}}::h1a2b3c4d5e6f7a8b"), "rust_ray::program::run::{closure}");
+        assert_eq!(shorten_symbol_name("rust_ray::program::run::{closure#0}"), "rust_ray::program::run::{closure}");
+    }
+
+    #[test]
+    fn shorten_symbol_skips_impl_blocks() {
+        assert_eq!(shorten_symbol_name("rust_ray::ui::impl$3::render<imgui::Ui>"), "rust_ray::ui::render");
+    }
+
+    #[test]
+    fn shorten_symbol_handles_arrows_in_generics() {
+        // The `>` of the arrow used to be treated as closing a generic, which underflowed the depth
+        assert_eq!(
+            shorten_symbol_name("<fn() -> core::result::Result<(), color_eyre::Report> as core::ops::function::FnOnce<()>>::call_once"),
+            "::call_once"
+        );
+    }
+
+    #[test]
+    fn shorten_symbol_handles_unmatched_close() {
+        assert_eq!(shorten_symbol_name("core::fmt::Display>::fmt"), "core::fmt::Display::fmt");
+    }
+
+    #[test]
+    fn shorten_symbol_handles_non_ascii() {
+        assert_eq!(shorten_symbol_name("rust_ray::ünïcödé::función::h0123456789abcdef"), "rust_ray::ünïcödé::función");
+        assert_eq!(shorten_symbol_name("rust_ray::ünïcödé<ß>::función"), "rust_ray::ünïcödé::función");
+    }
+
+    #[test]
+    fn split_span_fields_simple() {
+        assert_eq!(split_span_fields("a=1 b=two r#type=3"), vec!["a=1", "b=two", "r#type=3"]);
+    }
+
+    #[test]
+    fn split_span_fields_values_with_equals_and_commas() {
+        assert_eq!(split_span_fields(r#"a=x=y b="c=d, e=f" g=1, 2 h=3"#), vec!["a=x=y", r#"b="c=d, e=f""#, "g=1, 2", "h=3"]);
+    }
+
+    #[test]
+    fn split_span_fields_nested_braces() {
+        assert_eq!(
+            split_span_fields(r#"outer=Some(Inner { list: [(1, "a b=c"), (2, ")")], x=1 }) next=Foo { bar: Bar { baz=[] } } last=1"#),
+            vec![r#"outer=Some(Inner { list: [(1, "a b=c"), (2, ")")], x=1 })"#, "next=Foo { bar: Bar { baz=[] } }", "last=1"]
+        );
+    }
+
+    #[test]
+    fn split_span_fields_escaped_quotes() {
+        assert_eq!(split_span_fields(r#"msg="say \"hi\" x=1" n=2"#), vec![r#"msg="say \"hi\" x=1""#, "n=2"]);
+    }
+
+    #[test]
+    fn split_span_fields_char_literals() {
+        // A quote char used to start a string that never ended, merging all the fields after it
+        assert_eq!(split_span_fields(r#"c='"' d=2 e=3"#), vec![r#"c='"'"#, "d=2", "e=3"]);
+        assert_eq!(split_span_fields(r"c='\'' d='(' e='\u{1F600}' f=4"), vec![r"c='\''", "d='('", r"e='\u{1F600}'", "f=4"]);
+        // Not a char literal, just a lifetime in a type name
+        assert_eq!(split_span_fields("t=&'static str u=1"), vec!["t=&'static str", "u=1"]);
+    }
+
+    #[test]
+    fn parse_span_fields_keeps_repeated_values() {
+        let fields = parse_span_fields("x=1 y=Foo { a=1 } x=2");
+        assert_eq!(fields["x"], vec!["1", "2"]);
+        assert_eq!(fields["y"], vec!["Foo { a=1 }"]);
+    }
+}
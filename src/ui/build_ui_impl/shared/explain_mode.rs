@@ -0,0 +1,37 @@
+//! Explain mode: while it's on, hovering over a control shows a longer description of what it does
+//!
+//! Controls opt in by calling [explain_item()] straight after they're built, so the short tooltips shown normally can stay short
+use crate::config::read_config_value;
+use crate::helper::logging::event_targets::*;
+use imgui::Ui;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+use tracing::debug;
+
+/// Whether explain mode is currently on. Not saved in the config, since it's only meant to be turned on while learning the UI
+static EXPLAIN_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Whether explain mode is currently on
+pub fn explain_mode_enabled() -> bool {
+    EXPLAIN_MODE.load(Relaxed)
+}
+
+/// Turns explain mode on if it's off, and off if it's on
+pub fn toggle_explain_mode() {
+    let enabled = !EXPLAIN_MODE.fetch_xor(true, Relaxed);
+    debug!(target: UI_DEBUG_USER_INTERACTION, enabled, "toggled explain mode");
+}
+
+/// If explain mode is on and the last item is hovered, shows the `explanation` for it in a tooltip
+pub fn explain_item(ui: &Ui, explanation: &str) {
+    if !explain_mode_enabled() || !ui.is_item_hovered() {
+        return;
+    }
+    let colour = read_config_value(|config| config.runtime.ui.colours.severity.note);
+    ui.tooltip(|| {
+        // Some explanations are a few sentences long, so don't let them stretch across the whole screen
+        let wrap_token = ui.push_text_wrap_pos_with_pos(ui.current_font_size() * 30.0);
+        ui.text_colored(colour, explanation.trim());
+        wrap_token.end();
+    });
+}
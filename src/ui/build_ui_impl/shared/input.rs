@@ -1,16 +1,121 @@
-use crate::config::run_time::keybindings_config::KeyBinding;
+use crate::config::run_time::keybindings_config::{KeyBinding, KeyCode, KeybindingsConfig};
 use crate::helper::logging::event_targets::*;
+use crate::ui::build_ui_impl::shared::error_display::an_error_occurred;
+use color_eyre::section::SectionExt;
+use color_eyre::{Help, Report};
 use imgui::Ui;
-use tracing::{debug, trace, trace_span};
+use itertools::Itertools;
+use parking_lot::{const_mutex, Mutex};
+use tracing::{debug, trace, trace_span, warn};
 
-pub fn handle_shortcut(ui: &Ui, name: &str, keybind: &KeyBinding, toggle: &mut bool) {
-    trace_span!(target: UI_TRACE_USER_INPUT, "handle_shortcut", name, %keybind).in_scope(|| {
+/// Name of the keybinding that is waiting for the user to press a key (see [begin_keybinding_capture()]), if any
+static CAPTURING_KEYBINDING: Mutex<Option<&'static str>> = const_mutex(None);
+
+/// Toggles `toggle` if the shortcut `keybind` was pressed
+///
+/// If multiple keybindings in `all_keybindings` are bound to the same shortcut, none of them are triggered, since it's unclear which one the user wanted
+pub fn handle_shortcut(ui: &Ui, name: &str, keybind: &KeyBinding, all_keybindings: &KeybindingsConfig, toggle: &mut bool) {
+    if shortcut_pressed(ui, name, keybind, all_keybindings) {
+        *toggle ^= true;
+        debug!(target: UI_DEBUG_USER_INTERACTION, %keybind, "keybind for {} pressed, value: {}", name, toggle)
+    }
+}
+
+/// Checks whether the shortcut for a keybinding was pressed this frame (for actions that aren't just toggling a value, see [handle_shortcut])
+///
+/// Shortcuts are ignored while a keybinding is being captured, and when the shortcut is bound to multiple actions
+pub fn shortcut_pressed(ui: &Ui, name: &str, keybind: &KeyBinding, all_keybindings: &KeybindingsConfig) -> bool {
+    trace_span!(target: UI_TRACE_USER_INPUT, "shortcut_pressed", name, %keybind).in_scope(|| {
+        // Otherwise trying to rebind a key to an existing shortcut would also trigger that shortcut
+        if keybinding_capture_target().is_some() {
+            trace!(target: UI_TRACE_USER_INPUT, "capturing a keybinding, ignoring shortcut");
+            return false;
+        }
         let key_pressed = ui.is_key_index_pressed_no_repeat(keybind.shortcut as i32);
-        let modifiers_pressed = keybind.required_modifiers_held(ui);
+        let modifiers_pressed = keybind.modifiers_held(ui);
         trace!(target: UI_TRACE_USER_INPUT, key_pressed, modifiers_pressed);
-        if key_pressed && modifiers_pressed {
-            *toggle ^= true;
-            debug!(target: UI_DEBUG_USER_INTERACTION, %keybind, "keybind for {} pressed, value: {}", name, toggle)
+        if !(key_pressed && modifiers_pressed) {
+            return false;
+        }
+        let colliding = all_keybindings.names_bound_to(keybind);
+        if colliding.len() > 1 {
+            warn!(target: GENERAL_WARNING_NON_FATAL, %keybind, ?colliding, "keybind for {} pressed, but it is bound to multiple actions. ignoring", name);
+            return false;
+        }
+        true
+    })
+}
+
+/// Checks the keybindings for any that are bound to the same shortcut, and if there are any, shows an error to the user listing them
+pub fn report_keybinding_conflicts(keybindings: &KeybindingsConfig) {
+    let conflicts = keybindings.find_conflicts();
+    if conflicts.is_empty() {
+        trace!(target: UI_TRACE_USER_INPUT, "no keybinding conflicts");
+        return;
+    }
+    let conflicts_list = conflicts.iter().map(|(first, second)| format!("{first} <=> {second}")).join("\n");
+    an_error_occurred(
+        Report::msg("multiple keybindings are bound to the same shortcut")
+            .section(conflicts_list.header("Conflicting keybindings:"))
+            .note("conflicting shortcuts won't trigger any of their actions")
+            .suggestion("rebind one of each conflicting pair in the keybindings section of the config window"),
+    );
+}
+
+/// Returns the name of the keybinding that is currently being captured, if any
+pub fn keybinding_capture_target() -> Option<&'static str> {
+    *CAPTURING_KEYBINDING.lock()
+}
+
+/// Starts capturing the next key press for the keybinding with the given `name`. Any capture that was already in progress is cancelled
+pub fn begin_keybinding_capture(name: &'static str) {
+    debug!(target: UI_DEBUG_USER_INTERACTION, name, "capturing keybinding");
+    *CAPTURING_KEYBINDING.lock() = Some(name);
+}
+
+/// Checks if a key was pressed while capturing a keybinding, and if so returns the new keybinding and stops capturing
+///
+/// The modifiers (Ctrl/Alt/Shift) are taken from what's being held when the key is pressed, so they can't be the key itself.
+/// Pressing Escape cancels the capture
+pub fn poll_captured_keybinding(ui: &Ui) -> Option<KeyBinding> {
+    const MODIFIER_KEYS: [KeyCode; 8] = [
+        KeyCode::LShift,
+        KeyCode::RShift,
+        KeyCode::LControl,
+        KeyCode::RControl,
+        KeyCode::LAlt,
+        KeyCode::RAlt,
+        KeyCode::LWin,
+        KeyCode::RWin,
+    ];
+
+    if ui.is_key_index_pressed_no_repeat(KeyCode::Escape as i32) {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "cancelled keybinding capture");
+        *CAPTURING_KEYBINDING.lock() = None;
+        return None;
+    }
+
+    // The key indices that [imgui_winit_support] gives to imgui are just the [KeyCode]s cast to integers, so go through them all to find the pressed one
+    for index in 0..=(KeyCode::Cut as u32) {
+        if !ui.is_key_index_pressed_no_repeat(index as i32) {
+            continue;
         }
-    });
+        // SAFETY: [KeyCode] is `#[repr(u32)]`, and its discriminants are contiguous from 0 up to [KeyCode::Cut] (the last variant), so every index in this range is valid
+        let key = unsafe { std::mem::transmute::<u32, KeyCode>(index) };
+        if MODIFIER_KEYS.contains(&key) {
+            continue;
+        }
+
+        let io = ui.io();
+        let binding = KeyBinding {
+            shortcut: key,
+            modifier_ctrl: io.key_ctrl,
+            modifier_alt: io.key_alt,
+            modifier_shift: io.key_shift,
+        };
+        debug!(target: UI_DEBUG_USER_INTERACTION, %binding, "captured keybinding");
+        *CAPTURING_KEYBINDING.lock() = None;
+        return Some(binding);
+    }
+    None
 }
@@ -1,4 +1,5 @@
 use crate::helper::logging::event_targets::*;
+use crate::ui::build_ui_impl::shared::explain_mode::explain_item;
 use crate::FallibleFn;
 use imgui::Ui;
 use tracing::{debug, trace, trace_span};
@@ -24,7 +25,9 @@ pub fn menu<T: FnOnce() -> FallibleFn>(ui: &Ui, name: &str, generate_menu_items:
 }
 
 /// Creates a toggle menu item for a mutable bool reference
-pub fn toggle_menu_item(ui: &Ui, name: &str, toggle: &mut bool, shortcut_text: &str, tooltip: &str) -> FallibleFn {
+///
+/// Returns whether the user clicked it (i.e. whether `toggle` changed)
+pub fn toggle_menu_item(ui: &Ui, name: &str, toggle: &mut bool, shortcut_text: &str, tooltip: &str) -> bool {
     let span_toggle_menu_item = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "toggle_menu_item", toggle_name = name,).entered();
     trace!(target: UI_TRACE_BUILD_INTERFACE, toggle_value = toggle, shortcut_text, tooltip);
 
@@ -34,7 +37,30 @@ pub fn toggle_menu_item(ui: &Ui, name: &str, toggle: &mut bool, shortcut_text: &
         // Don't need to toggle manually since it's handled by ImGui (we passed in a mut ref to the variable)
         debug!(target: UI_DEBUG_USER_INTERACTION, "clicked menu item '{}', value: {}", name, *toggle);
     }
+    explain_item(ui, tooltip);
 
     span_toggle_menu_item.exit();
-    Ok(())
+    clicked
+}
+
+/// Creates a menu item that is one option out of a group of mutually exclusive options (like a radio button)
+///
+/// The item is shown as selected if `current == value`, and clicking it sets `current` to `value`. Returns whether `current` changed
+pub fn radio_menu_item<T: PartialEq>(ui: &Ui, name: &str, current: &mut T, value: T, shortcut_text: &str, tooltip: &str) -> bool {
+    let span_radio_menu_item = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "radio_menu_item", radio_name = name).entered();
+    let selected = *current == value;
+    trace!(target: UI_TRACE_BUILD_INTERFACE, selected, shortcut_text, tooltip);
+
+    let clicked = ui.menu_item_config(name).shortcut(shortcut_text).selected(selected).build();
+    trace!(target: UI_TRACE_USER_INPUT, name, clicked);
+    // Clicking the option that's already selected doesn't change anything
+    let changed = clicked && !selected;
+    if changed {
+        debug!(target: UI_DEBUG_USER_INTERACTION, "selected radio menu item '{}'", name);
+        *current = value;
+    }
+    explain_item(ui, tooltip);
+
+    span_radio_menu_item.exit();
+    changed
 }
@@ -7,6 +7,7 @@ use std::ffi::c_void;
 
 pub mod constants;
 pub mod error_display;
+pub mod explain_mode;
 pub mod input;
 pub mod menu_utils;
 pub mod tree_utils;
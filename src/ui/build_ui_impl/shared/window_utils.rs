@@ -19,7 +19,7 @@ pub fn build_window<T: UiItem>(label: &str, item: &mut T, opened: &mut bool, ui:
     result
 }
 
-pub fn build_window_fn(label: &str, func: fn(&Ui, bool) -> FallibleFn, opened: &mut bool, ui: &Ui) -> FallibleFn {
+pub fn build_window_fn(label: &str, func: impl FnOnce(&Ui, bool) -> FallibleFn, opened: &mut bool, ui: &Ui) -> FallibleFn {
     let span_window = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "build_window", window = label).entered();
     let mut result = Ok(());
     if *opened {
@@ -0,0 +1,66 @@
+//! The status bar along the bottom of the main window, giving an at-a-glance view of how the app is doing
+
+use crate::config::read_config_value;
+use crate::engine::{EngineData, EngineStatus};
+use crate::helper::logging::event_targets::*;
+use crate::ui::build_ui_impl::shared::error_display::last_error;
+use crate::ui::ui_data::{UiData, WindowId};
+use crate::ui::ui_system::UiManagers;
+use imgui::{Ui, WindowFlags};
+use std::time::Duration;
+use tracing::{debug, trace_span};
+
+/// How many of the most recent frames the FPS is averaged over, so the number is actually readable
+const FPS_AVERAGE_FRAMES: usize = 30;
+
+/// Height the status bar needs, so the space can be left free for it
+pub(in crate::ui) fn status_bar_height(ui: &Ui) -> f32 {
+    ui.frame_height_with_spacing()
+}
+
+/// Renders the status bar. Has to be called inside the main window, after the dockspace has been submitted (which should leave [status_bar_height()] free at the bottom)
+pub(in crate::ui) fn render_status_bar(ui: &Ui, managers: &UiManagers, data: &mut UiData, engine_data: EngineData) {
+    let _span = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "status_bar").entered();
+    let colours = read_config_value(|config| config.runtime.ui.colours);
+
+    ui.child_window("Status Bar").flags(WindowFlags::NO_SCROLLBAR | WindowFlags::NO_SCROLL_WITH_MOUSE).build(|| {
+        let fps = managers.frame_info.fps.iter().take(FPS_AVERAGE_FRAMES).copied().collect::<Vec<f32>>();
+        let average_fps = if fps.is_empty() { 0.0 } else { fps.iter().sum::<f32>() / fps.len() as f32 };
+        ui.text_colored(colours.value.number, format!("{average_fps:.0} FPS"));
+
+        ui.same_line();
+        ui.text_colored(colours.text.subtle, "|");
+        ui.same_line();
+        let engine_colour = match engine_data.status {
+            EngineStatus::Idle => colours.text.normal,
+            EngineStatus::Rendering { .. } => colours.text.accent,
+            EngineStatus::Finished { .. } => colours.severity.good,
+            EngineStatus::Paused => colours.severity.note,
+        };
+        ui.text_colored(engine_colour, format!("Engine: {} (iteration {})", engine_data.status, engine_data.iterations));
+
+        ui.same_line();
+        ui.text_colored(colours.text.subtle, "|");
+        ui.same_line();
+        ui.text_colored(colours.text.normal, format!("{} fonts loaded", managers.font_manager.fonts.len()));
+
+        ui.same_line();
+        ui.text_colored(colours.text.subtle, "|");
+        ui.same_line();
+        match last_error() {
+            None => ui.text_colored(colours.severity.good, "No errors"),
+            Some((message, time)) => {
+                // Round to seconds, or the display changes every frame and is unreadable
+                let ago = Duration::from_secs(time.elapsed().as_secs());
+                ui.text_colored(colours.severity.warning, format!("Last error ({} ago): {message}", humantime::format_duration(ago)));
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Click to open the error history");
+                }
+                if ui.is_item_clicked() {
+                    debug!(target: UI_DEBUG_USER_INTERACTION, "clicked last error in status bar, opening error history");
+                    *data.windows.open_mut(WindowId::ErrorHistory) = true;
+                }
+            }
+        }
+    });
+}
@@ -2,6 +2,7 @@ use crate::config::compile_time::ui_config::{MAX_FONT_SIZE, MIN_FONT_SIZE};
 use crate::config::read_config_value;
 use crate::helper::logging::event_targets::*;
 use crate::helper::logging::format_report_display;
+use crate::ui::build_ui_impl::shared::error_display::an_error_occurred;
 use crate::ui::build_ui_impl::UiItem;
 use crate::ui::font_manager::FontManager;
 use crate::FallibleFn;
@@ -29,6 +30,7 @@ impl UiItem for FontManager {
                 Err(err) => {
                     let report = err.wrap_err("could not reload fonts list from resources").note("called manually by user in font manager UI");
                     warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report));
+                    an_error_occurred(report);
                 }
             }
         }
@@ -116,19 +118,15 @@ impl UiItem for FontManager {
         }
 
         // # SELECTING FONT SIZE
-        let size = &mut self.selected_size;
+        let mut size = self.selected_size;
         trace!(target: UI_TRACE_BUILD_INTERFACE, "[slider] font size");
-        if ui.slider("Size (px)", MIN_FONT_SIZE, MAX_FONT_SIZE, size) {
+        if ui.slider("Size (px)", MIN_FONT_SIZE, MAX_FONT_SIZE, &mut size) {
             trace!(target: UI_DEBUG_USER_INTERACTION, "changed font size to {size} px");
-            if *size < MIN_FONT_SIZE {
-                warn!(target: GENERAL_WARNING_NON_FATAL, "font size ({size}) was < MIN_FONT_SIZE ({MIN_FONT_SIZE}), clamping");
-                *size = MIN_FONT_SIZE;
+            if !(MIN_FONT_SIZE..=MAX_FONT_SIZE).contains(&size) {
+                warn!(target: GENERAL_WARNING_NON_FATAL, "font size ({size}) was outside of MIN_FONT_SIZE..=MAX_FONT_SIZE ({MIN_FONT_SIZE}..={MAX_FONT_SIZE}), clamping");
             }
-            if *size > MAX_FONT_SIZE {
-                warn!(target: GENERAL_WARNING_NON_FATAL, "font size ({size}) was > MAX_FONT_SIZE ({MAX_FONT_SIZE}), clamping");
-                *size = MAX_FONT_SIZE;
-            }
-            *dirty = true;
+            // Also saves the size into the config, and clamps it for us
+            self.set_size(size);
         }
         trace!(target: UI_TRACE_BUILD_INTERFACE, "[tooltip] font size");
         if ui.is_item_hovered() {
@@ -1,69 +1,121 @@
 use crate::config::read_config_value;
 use crate::helper::logging::event_targets::*;
+use crate::helper::logging::format_report_display;
+use crate::helper::ring_buffer::RingBuffer;
+use crate::resources::resource_manager::{resource_folder_path, ResourceKind};
+use crate::ui::build_ui_impl::shared::error_display::an_error_occurred;
 use crate::ui::build_ui_impl::UiItem;
-use crate::ui::ui_system::FrameInfo;
+use crate::ui::ui_system::{FrameInfo, FramePhaseTimings, FrameStatistics};
 use crate::FallibleFn;
-use imgui::{TreeNodeFlags, Ui};
+use color_eyre::eyre::WrapErr;
+use color_eyre::{eyre, Help};
+use imgui::{TableFlags, TreeNodeFlags, Ui};
 use itertools::*;
-use std::cmp::min;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::field::Empty;
-use tracing::{trace, trace_span, warn};
+use tracing::{debug, trace, trace_span, warn};
 
 impl UiItem for FrameInfo {
     fn render(&mut self, ui: &Ui, mut visible: bool) -> FallibleFn {
         let span_render_framerate_graph = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "render_framerate_graph").entered();
         let config = &read_config_value(|config| config.runtime.ui.frame_info);
 
-        let track_frames = &config.num_frames_to_track;
+        let track_frames = config.num_frames_to_track;
         let deltas = &mut self.deltas;
         let fps = &mut self.fps;
+        let frame_number = &mut self.frame_number;
 
         // by placing this span before the header, we ensure that this always runs even when the header is collapsed
         trace_span!(target: UI_TRACE_MISC_PERFRAME_CALCULATIONS, "update_frame_infos").in_scope(|| {
             let delta = ui.io().delta_time;
-            // We insert into the front (start) of the Vec, then truncate the end, ensuring that the values get pushed along and we don't go over our limit
-            deltas.insert(0, delta * 1000.0);
-            fps.insert(0, 1f32 / delta);
-            deltas.truncate(*track_frames);
-            fps.truncate(*track_frames);
+            // Does nothing unless the config was changed
+            deltas.set_capacity(track_frames);
+            fps.set_capacity(track_frames);
+            // The ring buffers drop the oldest value once they're full, so we don't go over our limit
+            deltas.push(delta * 1000.0);
+            fps.push(1f32 / delta);
+            *frame_number += 1;
         });
 
-        fn chunked_smooth_minmax(vec: &[f32], chunk_size: usize) -> (f32, f32) {
-            vec.iter()
-                .chunks(chunk_size) // Group by 8 frames at a time
-                .into_iter()
-                .map(|chunk| {
-                    let mut count: f32 = 0.0;
-                    let mut avg = 0.0;
-                    for &val in chunk {
-                        avg += val;
-                        count += 1.0;
-                    }
-                    avg / count
-                }) //Average each chunk
-                .minmax()
-                .into_option()
-                .unwrap_or((0.0, 0.0))
+        /// Displays a table of statistics about the frame times (in milliseconds): mean, median, 95th/99th percentiles and the "1% low" FPS
+        ///
+        /// The averages hide stutters, but the percentiles and the 1% low (the average FPS of the slowest 1% of frames) don't.
+        /// The deltas are sorted in `scratch`, so we don't allocate a new buffer every frame
+        fn display_frame_statistics(ui: &Ui, deltas: &[f32], scratch: &mut Vec<f32>) {
+            let statistics = match FrameStatistics::calculate(deltas, scratch) {
+                None => return,
+                Some(statistics) => statistics,
+            };
+            let colours = read_config_value(|config| config.runtime.ui.colours);
+
+            let table_token = match ui.begin_table_with_flags("frame statistics table", 2, TableFlags::SIZING_FIXED_FIT) {
+                None => return,
+                Some(token) => token,
+            };
+            for (label, value) in [
+                ("mean", format!("{:.2} ms", statistics.mean)),
+                ("median", format!("{:.2} ms", statistics.median)),
+                ("95th percentile", format!("{:.2} ms", statistics.percentile_95)),
+                ("99th percentile", format!("{:.2} ms", statistics.percentile_99)),
+                ("1% low", format!("{:.2} fps", statistics.one_percent_low_fps)),
+            ] {
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text_colored(colours.value.value_label, label);
+                ui.table_next_column();
+                ui.text_colored(colours.value.number, value);
+            }
+            table_token.end();
         }
 
-        // ensures that we don't try to take a slice that's bigger than the amount we have in the Vec
-        // Don't have to worry about the `-1` if `len() == 0`, since len() should never `== 0`: we always have at least 1 frame since we insert above, and NUM_FRAMES_TO_DISPLAY should always be >=1
-        let num_frame_infos = trace_span!(target: UI_TRACE_MISC_PERFRAME_CALCULATIONS, "calc_num_frames").in_scope(|| {
-            let (len_d, len_f) = (deltas.len(), fps.len());
-            let len;
-            // We should always have the same number in both, but just to be safe, use the smaller one if they aren't the same
-            if len_d != len_f {
-                len = min(len_d, len_f);
-                warn!(
-                    target: GENERAL_WARNING_NON_FATAL,
-                    "did not have same number of delta and fps frame infos: (delta: {len_d}, fps: {len_f}). should be same. using {len}"
-                );
-            } else {
-                len = len_d;
+        /// Displays a stacked bar plot of how long each phase of the frames took, so it's easy to see where a frame's time goes
+        ///
+        /// There isn't a stacked plot in [imgui], so we draw the bars ourselves using the window's draw list
+        fn display_phase_timings_plot(ui: &Ui, phase_timings: &[FramePhaseTimings]) {
+            const PLOT_HEIGHT: f32 = 80.0;
+            let colours = read_config_value(|config| config.runtime.ui.colours);
+            let phase_colours = [colours.severity.very_bad, colours.severity.note, colours.severity.good, colours.severity.neutral];
+
+            if phase_timings.is_empty() {
+                ui.text_colored(colours.value.missing_value, "No phase timings recorded yet");
+                return;
+            }
+
+            let max_total = phase_timings.iter().map(|timings| timings.as_array().iter().sum::<f32>()).fold(0.0, f32::max);
+            let width = ui.content_region_avail()[0];
+            let origin = ui.cursor_screen_pos();
+            // Reserve the space for the plot, since drawing to the draw list doesn't move the cursor
+            ui.dummy([width, PLOT_HEIGHT]);
+
+            if max_total > 0.0 {
+                let draw_list = ui.get_window_draw_list();
+                let bar_width = width / phase_timings.len() as f32;
+                // Most recent frame is on the left, same as the other plots
+                for (index, timings) in phase_timings.iter().enumerate() {
+                    let x = origin[0] + (index as f32 * bar_width);
+                    let mut y = origin[1] + PLOT_HEIGHT;
+                    for (time, &colour) in timings.as_array().iter().zip(&phase_colours) {
+                        let bar_height = time / max_total * PLOT_HEIGHT;
+                        draw_list.add_rect([x, y - bar_height], [x + bar_width, y], <[f32; 4]>::from(colour)).filled(true).build();
+                        y -= bar_height;
+                    }
+                }
+            }
+
+            // Legend, with the latest timings
+            for ((name, time), colour) in FramePhaseTimings::PHASE_NAMES.iter().zip(phase_timings[0].as_array()).zip(phase_colours) {
+                ui.text_colored(colour, format!("{name}: {time:.2} ms"));
             }
-            len
+        }
+
+        // Copy out just the frames we're displaying, since the plots need a contiguous slice
+        let (displayed_deltas, displayed_fps) = (&mut self.scratch_deltas, &mut self.scratch_fps);
+        trace_span!(target: UI_TRACE_MISC_PERFRAME_CALCULATIONS, "copy_displayed_frames").in_scope(|| {
+            deltas.copy_recent_into(config.num_frames_to_display, displayed_deltas);
+            fps.copy_recent_into(config.num_frames_to_display, displayed_fps);
         });
-        let info_range_end = min(config.num_frames_to_display, num_frame_infos) - 1;
 
         //Try and find a rough range that the frame info values fall into. The values are smoothed so that they don't change instantaneously, or include outliers
         let (smooth_delta_min, smooth_delta_max);
@@ -77,10 +129,10 @@ impl UiItem for FrameInfo {
                 smooth_delta_max = Empty,
             )
             .entered();
-            let (sharp_delta_min, sharp_delta_max) = chunked_smooth_minmax(&deltas[0..info_range_end], config.chunked_average_smoothing_size);
+            let (sharp_delta_min, sharp_delta_max) = chunked_smooth_minmax(displayed_deltas, config.chunked_average_smoothing_size);
 
             // Update the local value, and then copy it across to the self value
-            // let (&sharp_delta_min, &sharp_delta_max) = deltas[0..info_range_end] // Slice the area that we're going to be displaying, or else we calculate on the area that isn't visible
+            // let (&sharp_delta_min, &sharp_delta_max) = displayed_deltas // Slice the area that we're going to be displaying, or else we calculate on the area that isn't visible
             //     .iter()
             //     .minmax()
             //     .into_option()
@@ -106,7 +158,27 @@ impl UiItem for FrameInfo {
             return Ok(());
         }
 
-        ui.plot_histogram(format!("{:0>5.2} .. {:0>5.2} ms", smooth_delta_min, smooth_delta_max), &deltas[0..info_range_end])
+        trace!(target: UI_TRACE_BUILD_INTERFACE, "[Button] Export Frame Times");
+        if ui.button("Export Frame Times") {
+            debug!(target: UI_DEBUG_USER_INTERACTION, "[Button] Export Frame Times pressed");
+            match export_frame_times(deltas, fps, &self.phase_timings, *frame_number) {
+                Ok(path) => debug!(target: UI_DEBUG_GENERAL, ?path, "exported frame times"),
+                Err(report) => {
+                    let report = report.wrap_err("could not export frame times");
+                    warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report));
+                    an_error_occurred(report);
+                }
+            }
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Writes the tracked frame times to a CSV file in the output folder");
+        }
+
+        // Only calculated when the header is open, since it needs a sorted copy of the displayed frames
+        let scratch_sorted = &mut self.scratch_sorted_deltas;
+        trace_span!(target: UI_TRACE_MISC_PERFRAME_CALCULATIONS, "frame_statistics").in_scope(|| display_frame_statistics(ui, displayed_deltas, scratch_sorted));
+
+        ui.plot_histogram(format!("{:0>5.2} .. {:0>5.2} ms", smooth_delta_min, smooth_delta_max), displayed_deltas)
             .overlay_text("ms/frame")
             .scale_min(if config.min_always_at_zero { 0.0 } else { smooth_delta_min })
             .scale_max(smooth_delta_max)
@@ -126,7 +198,7 @@ impl UiItem for FrameInfo {
             )
             .entered();
 
-            let (sharp_fps_min, sharp_fps_max) = chunked_smooth_minmax(&fps[0..info_range_end], config.chunked_average_smoothing_size);
+            let (sharp_fps_min, sharp_fps_max) = chunked_smooth_minmax(displayed_fps, config.chunked_average_smoothing_size);
             // Update the local value, and then copy it across to the self value
             smooth_fps_min = vek::Lerp::lerp(self.smooth_fps_min, sharp_fps_min, config.smooth_speed);
             self.smooth_fps_min = smooth_fps_min;
@@ -140,14 +212,118 @@ impl UiItem for FrameInfo {
             span_calculate_approx_range.exit();
         }
 
-        ui.plot_histogram(format!("{:0>6.2} .. {:>6.2} fps", smooth_fps_min, smooth_fps_max), &fps[0..info_range_end])
+        ui.plot_histogram(format!("{:0>6.2} .. {:>6.2} fps", smooth_fps_min, smooth_fps_max), displayed_fps)
             .overlay_text("frames/s")
             .scale_min(if config.min_always_at_zero { 0.0 } else { smooth_fps_min })
             .scale_max(smooth_fps_max)
             .build();
 
+        // Tell the UI thread to keep recording the phase timings, since we're displaying them
+        self.phase_timings_visible = true;
+        self.phase_timings.copy_recent_into(config.num_frames_to_display, &mut self.scratch_phase_timings);
+        trace_span!(target: UI_TRACE_BUILD_INTERFACE, "phase_timings_plot").in_scope(|| display_phase_timings_plot(ui, &self.scratch_phase_timings));
+
         span_render_framerate_graph.exit();
 
         Ok(())
     }
 }
+
+/// Exports the frame times (and the phase timings, if any were recorded) to a CSV file in the output folder, returning the path of the file
+///
+/// Each row is a frame, most recent first. Frames don't store when they happened, so the timestamp is how long before the export the frame was (in seconds), calculated from the deltas
+///
+/// `frame_number` is the [frame number](FrameInfo::frame_number) of the most recent delta, used to put the phase timings on the row of the frame they were recorded on
+fn export_frame_times(deltas: &RingBuffer<f32>, fps: &RingBuffer<f32>, phase_timings: &RingBuffer<FramePhaseTimings>, frame_number: u64) -> eyre::Result<PathBuf> {
+    let output_folder = resource_folder_path(ResourceKind::Output)?;
+    fs::create_dir_all(&output_folder)
+        .wrap_err("could not create output folder")
+        .with_note(|| format!("output folder: {}", output_folder.display()))?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let path = output_folder.join(format!("frame_times_{timestamp}.csv"));
+
+    let mut csv = String::from("seconds_ago,delta_ms,fps");
+    for name in FramePhaseTimings::PHASE_NAMES {
+        csv += &format!(",{}_ms", name.replace(' ', "_"));
+    }
+    csv.push('\n');
+    let mut seconds_ago = 0.0;
+    let mut phase_timings = phase_timings.iter().peekable();
+    for (frame, (delta, fps)) in (0..=frame_number).rev().zip(deltas.iter().zip(fps.iter())) {
+        csv += &format!("{seconds_ago},{delta},{fps}");
+        // Phase timings are only recorded while they're visible, so there might be gaps where frames don't have any
+        // Both are most recent first, so skip past any timings that are newer than this frame (which shouldn't happen, but would misalign everything after them)
+        while phase_timings.next_if(|timings| timings.frame > frame).is_some() {}
+        match phase_timings.next_if(|timings| timings.frame == frame) {
+            Some(timings) => timings.as_array().iter().for_each(|time| csv += &format!(",{time}")),
+            None => csv += &",".repeat(FramePhaseTimings::PHASE_NAMES.len()),
+        }
+        csv.push('\n');
+        seconds_ago += delta / 1000.0;
+    }
+
+    fs::write(&path, csv)
+        .wrap_err("could not write frame times to file")
+        .with_note(|| format!("file path: {}", path.display()))?;
+    Ok(path)
+}
+
+/// Averages each chunk of `chunk_size` values, and returns the smallest and largest of those averages (or `(0, 0)` if there are no values)
+///
+/// Used for the range of the frame time graphs, so that a single spike doesn't make the graph jump around
+fn chunked_smooth_minmax(vec: &[f32], chunk_size: usize) -> (f32, f32) {
+    vec.iter()
+        // `chunks()` panics with a size of 0, which could come from a hand-edited config
+        .chunks(chunk_size.max(1))
+        .into_iter()
+        .map(|chunk| {
+            let mut count: f32 = 0.0;
+            let mut avg = 0.0;
+            for &val in chunk {
+                avg += val;
+                count += 1.0;
+            }
+            avg / count
+        }) //Average each chunk
+        .minmax()
+        .into_option()
+        .unwrap_or((0.0, 0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn chunked_minmax_of_nothing() {
+        assert_eq!(chunked_smooth_minmax(&[], 4), (0.0, 0.0));
+    }
+
+    #[test]
+    fn chunked_minmax_averages_chunks() {
+        // Chunks are [1, 3] => 2, [10, 20] => 15, [4, 6] => 5
+        assert_eq!(chunked_smooth_minmax(&[1.0, 3.0, 10.0, 20.0, 4.0, 6.0], 2), (2.0, 15.0));
+    }
+
+    #[test]
+    fn chunked_minmax_smooths_spikes() {
+        // A single 100ms spike among 10ms frames only raises its chunk's average to (10 * 3 + 100) / 4 = 32.5
+        let deltas = [10.0, 10.0, 10.0, 100.0, 10.0, 10.0, 10.0, 10.0];
+        assert_eq!(chunked_smooth_minmax(&deltas, 4), (10.0, 32.5));
+    }
+
+    #[test]
+    fn chunked_minmax_partial_last_chunk() {
+        // The last chunk only has one value in it, so it's averaged on its own
+        assert_eq!(chunked_smooth_minmax(&[2.0, 4.0, 6.0, 8.0, 1.0], 2), (1.0, 7.0));
+    }
+
+    #[test]
+    fn chunked_minmax_single_value_chunks() {
+        // Chunks of 1 (or 0, which is treated the same) are just the plain min and max
+        let values = [5.0, 1.0, 9.0, 3.0];
+        assert_eq!(chunked_smooth_minmax(&values, 1), (1.0, 9.0));
+        assert_eq!(chunked_smooth_minmax(&values, 0), (1.0, 9.0));
+    }
+}
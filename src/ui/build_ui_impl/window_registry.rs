@@ -0,0 +1,118 @@
+//! The registry of windows that the user can show and hide
+//!
+//! The Tools menu, the keyboard shortcuts and building the windows are all driven from [WINDOWS], so adding a new window is just adding an entry (and a [WindowId]) here
+use crate::config::run_time::keybindings_config::{KeyBinding, KeybindingsConfig};
+use crate::helper::logging::event_targets::*;
+use crate::ui::build_ui_impl::config_ui_impl::render_config_ui;
+use crate::ui::build_ui_impl::help::render_help_window;
+use crate::ui::build_ui_impl::shared::error_display::render_error_history_window;
+use crate::ui::build_ui_impl::shared::window_utils::{build_window, build_window_fn};
+use crate::ui::ui_data::WindowId;
+use crate::ui::ui_system::UiManagers;
+use crate::FallibleFn;
+use imgui::Ui;
+use indoc::indoc;
+use tracing::{trace, trace_span};
+
+/// A window that can be shown/hidden by the user
+pub(super) struct RegisteredWindow {
+    /// Which window this is, used to find whether it's open in the [WindowRegistry](crate::ui::ui_data::WindowRegistry)
+    pub id: WindowId,
+    /// Title of the window, also used for the menu item
+    pub title: &'static str,
+    /// Tooltip for the window's menu item
+    pub description: &'static str,
+    /// Gets the keybinding that toggles the window
+    pub keybinding: fn(&KeybindingsConfig) -> KeyBinding,
+    /// Builds the window (if it's open), given the window's title. The `bool` is whether the window is open, and should be set to `false` if the window is closed
+    pub render: fn(&Ui, &str, &mut UiManagers, &mut bool) -> FallibleFn,
+}
+
+/// Gets the title of a registered window
+pub(in crate::ui) fn window_title(id: WindowId) -> &'static str {
+    WINDOWS.iter().find(|window| window.id == id).map_or("<unregistered window>", |window| window.title)
+}
+
+/// Every window that can be shown/hidden, in the order they appear in the Tools menu
+pub(super) const WINDOWS: [RegisteredWindow; WindowId::COUNT] = [
+    RegisteredWindow {
+        id: WindowId::Demo,
+        title: "Demo Window",
+        description: indoc! {r"
+            Toggles the ImGUI demo window.
+
+            The demo window demonstrates the features of Dear ImGUI, and provides some debugging tools for debugging ImGUI
+        "},
+        keybinding: |keys| keys.toggle_demo_window,
+        render: |ui, _, _, opened| {
+            if *opened {
+                trace_span!(target: UI_TRACE_BUILD_INTERFACE, "show_demo_window").in_scope(|| ui.show_demo_window(opened));
+            } else {
+                trace!(target: UI_TRACE_BUILD_INTERFACE, "demo window hidden");
+            }
+            Ok(())
+        },
+    },
+    RegisteredWindow {
+        id: WindowId::Metrics,
+        title: "Metrics",
+        description: indoc! {r"
+            Toggles the ImGUI metrics window.
+
+            The metrics window shows statistics and metrics about Dear ImGUI
+        "},
+        keybinding: |keys| keys.toggle_metrics_window,
+        render: |ui, _, _, opened| {
+            if *opened {
+                trace_span!(target: UI_TRACE_BUILD_INTERFACE, "show_metrics_window").in_scope(|| ui.show_metrics_window(opened));
+            } else {
+                trace!(target: UI_TRACE_BUILD_INTERFACE, "metrics window hidden");
+            }
+            Ok(())
+        },
+    },
+    RegisteredWindow {
+        id: WindowId::Config,
+        title: "Config",
+        description: indoc! {r"
+            Shows/hides the config window.
+
+            The config window allows modifying the app configuration. Very much WIP
+        "},
+        keybinding: |keys| keys.toggle_config_window,
+        render: |ui, title, managers, opened| build_window_fn(title, |ui, visible| render_config_ui(ui, visible, managers), opened, ui),
+    },
+    RegisteredWindow {
+        id: WindowId::UiManagement,
+        title: "UI Management",
+        description: indoc! {r"
+            Toggles the UI management window.
+
+            The UI management window allows you to control the UI, such as changing the font.
+        "},
+        keybinding: |keys| keys.toggle_ui_managers_window,
+        render: |ui, title, managers, opened| build_window(title, managers, opened, ui),
+    },
+    RegisteredWindow {
+        id: WindowId::ErrorHistory,
+        title: "Error History",
+        description: indoc! {r"
+            Toggles the error history window.
+
+            The error history window lists all the errors that have occurred, even ones that have been dismissed from the popup.
+        "},
+        keybinding: |keys| keys.toggle_error_history_window,
+        render: |ui, title, _, opened| build_window_fn(title, render_error_history_window, opened, ui),
+    },
+    RegisteredWindow {
+        id: WindowId::Help,
+        title: "Help",
+        description: indoc! {r"
+            Toggles the help window.
+
+            The help window lists every keybinding and what it does
+        "},
+        keybinding: |keys| keys.toggle_help_window,
+        render: |ui, title, _, opened| build_window_fn(title, render_help_window, opened, ui),
+    },
+];
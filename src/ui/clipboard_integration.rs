@@ -1,9 +1,12 @@
 //! Support module that allows for using the clipboard in [imgui]
 use std::any::type_name;
+use std::env;
 use std::fmt::{Debug, Formatter};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
 
 use clipboard::{ClipboardContext, ClipboardProvider};
-use color_eyre::{eyre, Help, SectionExt};
+use color_eyre::{eyre, Help, Report, SectionExt};
 use imgui::ClipboardBackend;
 use tracing::*;
 
@@ -26,12 +29,73 @@ impl Debug for ImguiClipboardSupport {
     }
 }
 
+/// Whether the clipboard was initialised successfully. See [clipboard_available]
+static CLIPBOARD_AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the clipboard was initialised successfully
+///
+/// If it wasn't, [imgui] falls back to its own internal clipboard, so copying still "works" but never reaches other apps.
+/// UI that copies to the clipboard should check this, so it can tell the user instead of silently doing nothing
+pub(in crate::ui) fn clipboard_available() -> bool {
+    CLIPBOARD_AVAILABLE.load(Relaxed)
+}
+
+/// The reasons the clipboard couldn't be initialised
+///
+/// These are wrapped in a [Report] by [clipboard_init], so callers can use [Report::downcast_ref] to check which one happened
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub(in crate::ui) enum ClipboardInitError {
+    /// There's no display server at all (e.g. running headless), so there's no clipboard to connect to
+    #[error("no display server to get the clipboard from")]
+    NoDisplayServer,
+    /// Running under Wayland without XWayland. The clipboard backend only supports X11 on unix
+    #[error("the clipboard is not supported on Wayland without XWayland")]
+    WaylandWithoutX11,
+    /// The platform's clipboard failed for some other reason
+    #[error("the platform clipboard could not be opened")]
+    Platform,
+}
+
+impl ClipboardInitError {
+    /// Works out the most likely reason the clipboard failed to initialise, from the environment
+    fn diagnose() -> Self {
+        // Only unix (non-macOS) uses a display server for the clipboard, everywhere else it's always there
+        if !cfg!(all(unix, not(target_os = "macos"))) {
+            return ClipboardInitError::Platform;
+        }
+        match (env::var_os("DISPLAY").is_some(), env::var_os("WAYLAND_DISPLAY").is_some()) {
+            (false, false) => ClipboardInitError::NoDisplayServer,
+            (false, true) => ClipboardInitError::WaylandWithoutX11,
+            (true, _) => ClipboardInitError::Platform,
+        }
+    }
+
+    /// What the user can do to fix this error
+    fn suggestion(self) -> &'static str {
+        match self {
+            ClipboardInitError::NoDisplayServer => "make sure the `DISPLAY` environment variable is set to a running X server",
+            ClipboardInitError::WaylandWithoutX11 => "enable XWayland (so that `DISPLAY` gets set), or run the app under an X11 session",
+            ClipboardInitError::Platform => "check the error below for what the platform reported",
+        }
+    }
+}
+
 /// (Tries to) initialise clipboard support
+///
+/// On failure, the [Report] contains a [ClipboardInitError] saying why
 pub(in crate::ui) fn clipboard_init() -> eyre::Result<ImguiClipboardSupport> {
     match ClipboardContext::new() {
-        Ok(val) => Ok(ImguiClipboardSupport { backing_context: val }),
+        Ok(val) => {
+            CLIPBOARD_AVAILABLE.store(true, Relaxed);
+            Ok(ImguiClipboardSupport { backing_context: val })
+        }
         Err(boxed_error) => {
-            let report = dyn_error_to_report(&boxed_error).wrap_err("could not get clipboard context");
+            let cause = ClipboardInitError::diagnose();
+            let report = Report::new(cause)
+                .wrap_err("could not get clipboard context")
+                .section(dyn_error_to_report(&boxed_error).to_string().header("Platform error:"))
+                .suggestion(cause.suggestion())
+                .note("copying will only work inside the app until this is fixed");
             Err(report)
         }
     }
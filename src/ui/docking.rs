@@ -4,6 +4,7 @@
 
 use std::os::raw::c_char;
 
+use crate::ui::ui_data::WindowId;
 use imgui::{sys, Direction};
 
 /// Rust version of  the Dear ImGUI struct DockNode
@@ -67,6 +68,12 @@ impl DockNode {
     }
 }
 
+/// Label of the dockspace that covers the main window, that all the other windows get docked into
+pub const MAIN_DOCK_AREA_NAME: &str = "Main Dock Area";
+
+/// `ImGuiDockNodeFlags_DockSpace`, which is only in the internal API so isn't exported by [sys]
+const DOCK_NODE_FLAGS_DOCKSPACE: i32 = 1 << 10;
+
 //TODO: Find out what's necessary in these files. May be able to remove UiDockingArea struct completely
 /// # Docking
 pub struct UiDockingArea {}
@@ -84,15 +91,48 @@ impl UiDockingArea {
     /// dockspace which can be used to, say, programmatically split or
     /// dock windows into it
     #[doc(alias = "DockSpace")]
-    pub fn dockspace(&self, label: &str) -> DockNode {
+    /// Submits the dockspace. A `size` of `0` on an axis fills the rest of the window, and a negative size fills all but that much
+    pub fn dockspace(&self, label: &str, size: [f32; 2]) -> DockNode {
         let label = imgui::ImString::from(label.to_string());
         unsafe {
             let id = sys::igGetIDStr(label.as_ptr() as *const c_char);
-            sys::igDockSpace(id, [0.0, 0.0].into(), 0, ::std::ptr::null::<sys::ImGuiWindowClass>());
+            sys::igDockSpace(id, size.into(), 0, ::std::ptr::null::<sys::ImGuiWindowClass>());
             DockNode { id }
         }
     }
 
+    /// Clears the dockspace with the given label, and rebuilds it with the default layout:
+    /// UI management on the left, config on the right, error history along the bottom, and the centre left free for the viewport.
+    /// Windows that aren't mentioned (e.g. the demo window) are left floating
+    ///
+    /// Must be called before [dockspace()](UiDockingArea::dockspace) in the frame, and the `size` should be the size the dockspace will be
+    pub fn build_default_layout(&self, label: &str, size: [f32; 2], window_title: impl Fn(WindowId) -> &'static str) {
+        let label = imgui::ImString::from(label.to_string());
+        let id = unsafe {
+            let id = sys::igGetIDStr(label.as_ptr() as *const c_char);
+            sys::igDockBuilderRemoveNode(id);
+            sys::igDockBuilderAddNode(id, DOCK_NODE_FLAGS_DOCKSPACE);
+            sys::igDockBuilderSetNodeSize(id, size.into());
+            id
+        };
+
+        DockNode::new(id).split(
+            Direction::Left,
+            0.2,
+            |left| left.dock_window(window_title(WindowId::UiManagement)),
+            |rest| {
+                rest.split(
+                    Direction::Right,
+                    0.25,
+                    |right| right.dock_window(window_title(WindowId::Config)),
+                    |centre| centre.split(Direction::Down, 0.25, |bottom| bottom.dock_window(window_title(WindowId::ErrorHistory)), |_viewport| ()),
+                )
+            },
+        );
+
+        unsafe { sys::igDockBuilderFinish(id) }
+    }
+
     /// (Unsure) Moves the dockspace to be over the main viewport (that seems to be hardcoded in)
     #[doc(alias = "DockSpaceOverViewport")]
     pub fn dockspace_over_viewport(&self) {
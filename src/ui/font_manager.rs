@@ -9,16 +9,18 @@ use nameof::name_of;
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::fs;
-use std::io::Read;
 use std::ops::Deref;
 use tracing::warn;
 use tracing::{debug, debug_span, trace, trace_span};
 
-use crate::config::compile_time::resources_config::{FONTS_FILE_NAME_EXTRACTOR, FONTS_FILE_PATH_FILTER};
+use crate::config::compile_time::resources_config::{
+    FALLBACK_FONT_DATA, FALLBACK_FONT_NAME, FALLBACK_FONT_WEIGHT_NAME, FONTS_FILE_NAME_EXTRACTOR, FONTS_FILE_PATH_FILTER,
+};
 use crate::config::compile_time::ui_config::{MAX_FONT_SIZE, MIN_FONT_SIZE};
-use crate::config::read_config_value;
+use crate::config::{read_config_value, update_config};
 use crate::helper::logging::event_targets::*;
-use crate::resources::resource_manager::get_main_resource_folder_path;
+use crate::helper::logging::format_report_display;
+use crate::resources::resource_manager::{resource_folder_path, ResourceKind};
 use crate::FallibleFn;
 
 #[derive(Debug, Clone)]
@@ -49,12 +51,28 @@ impl FontManager {
         */
         self.dirty = true;
 
-        let fonts_directory_path = get_main_resource_folder_path()?.join(read_config_value(|config| config.runtime.resources.fonts_path.clone()));
+        // If we can't find any fonts, we still want a usable UI, so fall back to the embedded font instead of failing
+        let fonts_directory_path = match resource_folder_path(ResourceKind::Fonts) {
+            Ok(path) => path,
+            Err(report) => {
+                self.use_fallback_font(report.wrap_err("could not get resources folder"));
+                span_reload_fonts_list.exit();
+                return Ok(());
+            }
+        };
 
         debug!(target: RESOURCES_DEBUG_LOAD, "reloading fonts from resources folder {:?}", fonts_directory_path);
-        let fonts_dir_content = dir::get_dir_content(&fonts_directory_path)
+        let fonts_dir_content = match dir::get_dir_content(&fonts_directory_path)
             .wrap_err("could not load fonts directory")
-            .note(format!("Attempted to load from {:?}", fonts_directory_path))?;
+            .note(format!("Attempted to load from {:?}", fonts_directory_path))
+        {
+            Ok(content) => content,
+            Err(report) => {
+                self.use_fallback_font(report);
+                span_reload_fonts_list.exit();
+                return Ok(());
+            }
+        };
 
         debug!(target: DATA_DEBUG_DUMP_OBJECT, size=fonts_dir_content.dir_size, directories=?fonts_dir_content.directories, files=?fonts_dir_content.files);
 
@@ -63,9 +81,6 @@ impl FontManager {
         let name_extractor_regex = FONTS_FILE_NAME_EXTRACTOR.deref();
         debug!(target: DATA_DEBUG_DUMP_OBJECT, font_name_extractor_regex=?name_extractor_regex);
 
-        // We read the file into this buffer before we process it
-        let mut font_data_buffer = Vec::with_capacity(512 * 1024 /*512kb default*/);
-
         // Nested hashmaps store data
         // First layer is [base font name]
         // Second layer contains [weight name] and font data
@@ -79,23 +94,21 @@ impl FontManager {
                 }
                 trace!(target: FONT_MANAGER_TRACE_FONT_LOAD, "reading matching file at {file_path}");
 
-                let mut file = match fs::File::open(file_path) {
-                    Ok(file) => file,
-                    Err(err) => {
-                        let report = Report::new(err).wrap_err(format!("was not able to open font file at {file_path}"));
+                // The directory listing already gives us full paths inside the fonts folder, so there's no need to resolve them again
+                let font_data_buffer = match fs::read(file_path) {
+                    Ok(data) => data,
+                    Err(error) => {
+                        let report = Report::new(error).wrap_err(format!("could not read font file at {file_path}"));
                         warn!(target: RESOURCES_WARNING_NON_FATAL, ?report);
                         continue;
                     }
                 };
 
-                font_data_buffer.clear();
-                match file.read_to_end(&mut font_data_buffer) {
-                    Ok(_read) => {}
-                    Err(error) => {
-                        let report = Report::new(error).wrap_err(format!("could not read bytes from font file at {file_path}"));
-                        warn!(target: RESOURCES_WARNING_NON_FATAL, ?report);
-                        continue;
-                    }
+                // Passing garbage to imgui is likely to crash the renderer when the atlas is built, so make sure it at least looks like a font
+                if let Err(report) = validate_font_data(&font_data_buffer) {
+                    let report = report.wrap_err(format!("font file at {file_path} was invalid, skipping"));
+                    warn!(target: RESOURCES_WARNING_NON_FATAL, report = format_report_display(&report));
+                    continue;
                 }
 
                 // Extract font names from the file path using Regex
@@ -173,6 +186,10 @@ impl FontManager {
             }
         });
 
+        if self.fonts.is_empty() {
+            self.use_fallback_font(Report::msg("no fonts were found in the fonts directory").note(format!("Loaded from {:?}", fonts_directory_path)));
+        }
+
         /*
         Now that we have a new list, make sure that our indices are still valid
         Also mark as dirty for rebuild, just in case
@@ -218,12 +235,48 @@ impl FontManager {
         Ok(())
     }
 
+    /// Replaces the list of fonts with just the font embedded in the binary (see [FALLBACK_FONT_DATA]), logging the `reason` why as a warning
+    fn use_fallback_font(&mut self, reason: Report) {
+        warn!(
+            target: RESOURCES_WARNING_NON_FATAL,
+            report = format_report_display(&reason),
+            "could not load fonts from resources, using embedded fallback font {FALLBACK_FONT_NAME}"
+        );
+        self.fonts = vec![Font {
+            name: FALLBACK_FONT_NAME.to_string(),
+            weights: vec![FontWeight {
+                name: FALLBACK_FONT_WEIGHT_NAME.to_string(),
+                data: FALLBACK_FONT_DATA.to_vec(),
+            }],
+        }];
+        self.selected_font_index = 0;
+        self.selected_weight_index = 0;
+        self.dirty = true;
+    }
+
+    /// Changes the font size by `delta` pixels (clamped to [MIN_FONT_SIZE]..[MAX_FONT_SIZE]), and saves the new size in the config
+    pub fn adjust_size(&mut self, delta: f32) {
+        self.set_size(self.selected_size + delta);
+    }
+
+    /// Sets the font size (clamped to [MIN_FONT_SIZE]..[MAX_FONT_SIZE]), and saves the new size in the config
+    pub fn set_size(&mut self, size: f32) {
+        let size = size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+        if size == self.selected_size {
+            return;
+        }
+        debug!(target: UI_DEBUG_GENERAL, old_size = self.selected_size, new_size = size, "changing font size");
+        self.selected_size = size;
+        self.dirty = true;
+        update_config(|config| config.runtime.ui.font_size = size);
+    }
+
     pub fn new() -> eyre::Result<Self> {
         let manager = FontManager {
             fonts: vec![],
             selected_font_index: 0,
             selected_weight_index: 0,
-            selected_size: 20f32, //TODO: Font size and weights in config
+            selected_size: read_config_value(|config| config.runtime.ui.font_size),
 
             current_font: None,
             dirty: true,
@@ -238,6 +291,14 @@ impl FontManager {
     /// Note:
     /// If this returns `Ok(true)`, you ***MUST*** call `renderer.reload_font_texture(imgui_context)` or the app will crash
     pub fn rebuild_font_if_needed(&mut self, font_atlas: &mut FontAtlas) -> eyre::Result<bool> {
+        // The size might have been changed in the config (e.g. the config window, or reloading from disk), so keep in sync with that
+        let config_size = read_config_value(|config| config.runtime.ui.font_size);
+        if config_size != self.selected_size {
+            debug!(target: UI_DEBUG_GENERAL, old_size = self.selected_size, new_size = config_size, "font size changed in config");
+            self.selected_size = config_size;
+            self.dirty = true;
+        }
+
         // Don't need to update if we already have a font and we're not dirty
         if !self.dirty && self.current_font.is_some() {
             return Ok(false);
@@ -280,7 +341,7 @@ impl FontManager {
         debug!(target: DATA_DEBUG_DUMP_OBJECT, data = ?weight.data);
 
         let full_name = format!("{name} - {weight} ({size}px)", name = base_font.name, weight = weight.name).into();
-        //TODO: What happens if a font file has invalid font data (or isn't a font file)
+        // Font data should have been checked by [validate_font_data()] when the list was loaded, so hopefully imgui won't choke on it
         let oversampling = read_config_value(|config| config.runtime.ui.font_oversampling);
         let font_id = font_atlas.add_font(&[FontSource::TtfData {
             data: &weight.data,
@@ -344,3 +405,139 @@ impl Debug for FontWeight {
             .finish_non_exhaustive()
     }
 }
+
+/// Tables that must be present in a font for imgui (stb_truetype) to be able to use it
+const REQUIRED_FONT_TABLES: [&[u8; 4]; 4] = [b"cmap", b"head", b"hhea", b"hmtx"];
+
+/// Does a quick sanity check that the given data is a TrueType/OpenType font, by checking the magic number and table directory.
+///
+/// This won't catch every kind of corruption, but it does stop obviously wrong files (e.g. a text file named `.ttf`) getting passed to imgui
+fn validate_font_data(data: &[u8]) -> FallibleFn {
+    let read_u16 = |offset: usize| data.get(offset..offset + 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+    let read_u32 = |offset: usize| data.get(offset..offset + 4).map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+
+    let magic = data
+        .get(0..4)
+        .ok_or_else(|| Report::msg("font data too short to contain a header"))
+        .note(format!("length: {} bytes", data.len()))?;
+    match magic {
+        // TrueType, OpenType (CFF), Apple TrueType and PostScript-in-TrueType
+        [0x00, 0x01, 0x00, 0x00] | b"OTTO" | b"true" | b"typ1" => {}
+        // Font collections have a different header, with the table directories for each font further in the file
+        b"ttcf" => {
+            return match read_u32(8) {
+                Some(num_fonts) if num_fonts > 0 => Ok(()),
+                _ => Err(Report::msg("font collection contained no fonts")),
+            };
+        }
+        _ => {
+            return Err(Report::msg("font data had an invalid magic number"))
+                .note(format!("magic number: {magic:02X?}"))
+                .suggestion("ensure the file is a TrueType (.ttf) or OpenType (.otf) font")
+        }
+    }
+
+    let num_tables = read_u16(4).ok_or_else(|| Report::msg("font data too short to contain the number of tables"))? as usize;
+    let mut found_tables = Vec::with_capacity(num_tables);
+    for index in 0..num_tables {
+        // The table directory starts after the 12 byte header, and each record is 16 bytes: tag, checksum, offset, length
+        let record = 12 + (index * 16);
+        let (tag, offset, length) = match (data.get(record..record + 4), read_u32(record + 8), read_u32(record + 12)) {
+            (Some(tag), Some(offset), Some(length)) => (tag, offset, length),
+            _ => return Err(Report::msg("font table directory was truncated")).note(format!("expected {num_tables} tables, could only read {index}")),
+        };
+        if (offset as usize).saturating_add(length as usize) > data.len() {
+            return Err(Report::msg(format!("font table `{}` extends past the end of the data", String::from_utf8_lossy(tag))))
+                .note(format!("table offset: {offset}, length: {length}, data length: {}", data.len()));
+        }
+        found_tables.push(tag);
+    }
+
+    for required in REQUIRED_FONT_TABLES {
+        if !found_tables.contains(&required.as_slice()) {
+            return Err(Report::msg(format!("font data is missing the required `{}` table", String::from_utf8_lossy(required))));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds the header and table directory of a TrueType font, with the given `(tag, offset, length)` tables
+    fn font_header(tables: &[(&[u8; 4], u32, u32)]) -> Vec<u8> {
+        let mut data = vec![0x00, 0x01, 0x00, 0x00];
+        data.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+        // Search range, entry selector and range shift, which we don't check
+        data.extend_from_slice(&[0; 6]);
+        for (tag, offset, length) in tables {
+            data.extend_from_slice(*tag);
+            data.extend_from_slice(&0u32.to_be_bytes());
+            data.extend_from_slice(&offset.to_be_bytes());
+            data.extend_from_slice(&length.to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn accepts_embedded_font() {
+        assert!(validate_font_data(FALLBACK_FONT_DATA).is_ok());
+    }
+
+    #[test]
+    fn accepts_resource_font() {
+        let data = include_bytes!("../resources/app_resources/fonts/fira code/Fira Code (Regular).ttf");
+        assert!(validate_font_data(data).is_ok());
+    }
+
+    #[test]
+    fn accepts_minimal_font() {
+        let tables = REQUIRED_FONT_TABLES.map(|tag| (tag, 0, 0));
+        assert!(validate_font_data(&font_header(&tables)).is_ok());
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(validate_font_data(&[]).is_err());
+        assert!(validate_font_data(&[0x00, 0x01]).is_err());
+        assert!(validate_font_data(b"this is a text file, not a font").is_err());
+        assert!(validate_font_data(&[0xAB; 1024]).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_table_directory() {
+        let tables = REQUIRED_FONT_TABLES.map(|tag| (tag, 0, 0));
+        let data = font_header(&tables);
+        assert!(validate_font_data(&data[..data.len() - 1]).is_err());
+        // Valid magic number, but cut off before the number of tables
+        assert!(validate_font_data(&data[..5]).is_err());
+    }
+
+    #[test]
+    fn rejects_missing_required_table() {
+        let tables = REQUIRED_FONT_TABLES.map(|tag| (tag, 0, 0));
+        assert!(validate_font_data(&font_header(&tables[1..])).is_err());
+    }
+
+    #[test]
+    fn rejects_table_past_end() {
+        let mut tables = REQUIRED_FONT_TABLES.map(|tag| (tag, 0, 0));
+        tables[0].2 = 1_000_000;
+        assert!(validate_font_data(&font_header(&tables)).is_err());
+    }
+
+    #[test]
+    fn font_collection_needs_fonts() {
+        let mut collection = b"ttcf".to_vec();
+        // Version, then number of fonts
+        collection.extend_from_slice(&[0x00, 0x01, 0x00, 0x00]);
+        collection.extend_from_slice(&0u32.to_be_bytes());
+        assert!(validate_font_data(&collection).is_err());
+
+        collection.truncate(8);
+        collection.extend_from_slice(&1u32.to_be_bytes());
+        assert!(validate_font_data(&collection).is_ok());
+    }
+}
@@ -1,53 +1,66 @@
-use std::sync::mpsc::TrySendError::{Disconnected, Full};
-use std::sync::{Arc, Barrier, Mutex, TryLockError};
-use std::thread::sleep;
+use std::fs;
+use std::sync::{Arc, Barrier};
 use std::time::{Duration, Instant};
 
 use color_eyre::eyre::WrapErr;
-use color_eyre::{eyre, Help, Report};
+use color_eyre::{eyre, Help, Report, SectionExt};
 use glium::glutin::event_loop::ControlFlow;
 use glium::glutin::platform::run_return::EventLoopExtRunReturn;
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+use glium::glutin::platform::unix::EventLoopBuilderExtUnix;
+#[cfg(windows)]
 use glium::glutin::platform::windows::EventLoopBuilderExtWindows;
 use glium::glutin::CreationError::NoAvailablePixelFormat;
-use glium::{glutin, Display, Surface};
+use glium::{glutin, Display};
 use imgui::sys::{igColorConvertHSVtoRGB, igColorConvertRGBtoHSV};
 use imgui::Condition::Always;
 use imgui::{ColorStackToken, Context, StyleColor, StyleVar, WindowFlags};
-use imgui_glium_renderer::Renderer;
 use imgui_winit_support::winit::event_loop::EventLoopBuilder;
-use imgui_winit_support::winit::window::WindowBuilder;
-use imgui_winit_support::{HiDpiMode, WinitPlatform};
+use imgui_winit_support::winit::dpi::{PhysicalPosition, PhysicalSize};
+use imgui_winit_support::winit::event_loop::EventLoop;
+use imgui_winit_support::winit::monitor::MonitorHandle;
+use imgui_winit_support::winit::window::{Window, WindowBuilder};
+use imgui_winit_support::WinitPlatform;
 use mint::Vector4;
 use multiqueue2::{BroadcastReceiver, BroadcastSender};
 use nameof::name_of;
+use parking_lot::{Mutex, MutexGuard};
 use tracing::field::{debug, Empty};
 use tracing::{debug, debug_span, error, info, info_span, trace, trace_span, warn};
 use vek::num_integer::div_rem;
 use vek::num_traits::clamp;
 
 use crate::build::*;
-use crate::config::read_config_value;
+use crate::config::compile_time::ui_config::POWER_SAVING_MAX_IDLE;
+use crate::config::{read_config_value, update_config};
 use crate::config::run_time::ui_config::theme::Theme;
+use crate::engine::EngineData;
 use crate::helper::logging::event_targets::*;
 use crate::helper::logging::format_report_display;
 use crate::program::program_data::ProgramData;
 use crate::program::thread_messages::ThreadMessage::{Engine, Program, Ui};
 use crate::program::thread_messages::*;
-use crate::ui::build_ui_impl::build_ui;
-use crate::ui::docking::UiDockingArea;
+use crate::ui::build_ui_impl::{an_error_occurred, build_ui, render_status_bar, request_quit, status_bar_height, window_title};
+use crate::ui::docking::{UiDockingArea, MAIN_DOCK_AREA_NAME};
+use crate::resources::resource_manager::{get_main_resource_folder_path, ResourceKind};
+use crate::resources::resource_watcher::ResourceWatcher;
 use crate::ui::font_manager::FontManager;
+use crate::ui::renderer::{create_renderer, UiRenderer};
+use crate::ui::screenshot::save_screenshot;
 use crate::ui::ui_data::UiData;
-use crate::ui::ui_system::{FrameInfo, UiBackend, UiManagers, UiSystem};
+use crate::ui::ui_system::{FrameInfo, FramePhaseTimings, Toast, UiBackend, UiManagers, UiSystem};
+use crate::ui::window_status::{update_window_status, TaskbarProgress};
 use crate::FallibleFn;
-use ProgramThreadMessage::QuitAppNoError;
-use QuitAppNoErrorReason::QuitInteractionByUser;
 
 mod build_ui_impl;
 mod clipboard_integration;
 mod docking;
 mod font_manager;
+mod renderer;
+mod screenshot;
 pub mod ui_data;
 mod ui_system;
+mod window_status;
 
 pub(crate) fn ui_thread(
     thread_start_barrier: Arc<Barrier>,
@@ -94,6 +107,12 @@ pub(crate) fn ui_thread(
     //It's not unused [event_loop_return()] macro uses it but it's not recognised
     let result_ref = &mut result;
     let mut last_frame = Instant::now();
+    // Used for limiting how often we redraw
+    let mut last_redraw = Instant::now();
+    let mut input_since_redraw = true;
+    let (frame_limit, power_saving) = read_config_value(|config| (config.init.ui_config.frame_limit, config.init.ui_config.power_saving));
+    let frame_interval = frame_limit.filter(|&limit| limit > 0.0).map(|limit| Duration::from_secs_f32(1.0 / limit));
+    debug!(target: UI_DEBUG_GENERAL, ?frame_interval, power_saving);
 
     debug!(target: UI_DEBUG_GENERAL, "running event loop");
     let span_event_loop_internal = debug_span!(target: UI_DEBUG_GENERAL, "event_loop_internal").entered();
@@ -125,8 +144,21 @@ pub(crate) fn ui_thread(
             glutin::event::Event::MainEventsCleared => {
                 let gl_window = display.gl_window();
                 let window = gl_window.window();
-                //Pretty sure this makes us render constantly since we always want the app to be drawing (realtime application remember)
-                trace_span!(target: UI_TRACE_EVENT_LOOP, "request_redraw").in_scope(|| window.request_redraw());
+                // Normally we render constantly since we always want the app to be drawing (realtime application remember)
+                // But if there's a frame limit, or we're saving power, wait until it's time for the next frame instead
+                let now = Instant::now();
+                let next_frame = frame_interval.map_or(now, |interval| last_redraw + interval);
+                let wants_redraw = !power_saving || input_since_redraw || now - last_redraw >= POWER_SAVING_MAX_IDLE;
+                if *control_flow != ControlFlow::Exit {
+                    if wants_redraw && now >= next_frame {
+                        *control_flow = ControlFlow::Poll;
+                        trace_span!(target: UI_TRACE_EVENT_LOOP, "request_redraw").in_scope(|| window.request_redraw());
+                    } else {
+                        let wake_at = if wants_redraw { next_frame } else { last_redraw + POWER_SAVING_MAX_IDLE };
+                        trace!(target: UI_TRACE_EVENT_LOOP, wait = ?(wake_at - now), "waiting until next frame");
+                        *control_flow = ControlFlow::WaitUntil(wake_at);
+                    }
+                }
 
                 trace_span!(target: UI_TRACE_EVENT_LOOP, "prepare_frame").in_scope(|| {
                     let result = platform.prepare_frame(imgui_context.io_mut(), window);
@@ -142,44 +174,13 @@ pub(crate) fn ui_thread(
 
             glutin::event::Event::RedrawRequested(_) => {
                 let span_redraw = trace_span!(target: UI_TRACE_EVENT_LOOP, "redraw").entered();
+                last_redraw = Instant::now();
+                input_since_redraw = false;
 
-                let mut program_data = {
-                    const MUTEX_LOCK_RETRY_DELAY: Duration = Duration::from_millis(1);
-
-                    let span_obtain_data = trace_span!(target: THREAD_TRACE_MUTEX_SYNC, "obtain_data", ?MUTEX_LOCK_RETRY_DELAY, tries = Empty, time_taken_to_obtain = Empty).entered();
-
-                    let mut tries = 0;
-                    let start = Instant::now();
-                    let program_data = loop {
-                        tries += 1;
-                        match program_data_wrapped.try_lock() {
-                            //Shouldn't get here, since the engine/main threads shouldn't panic (and the app should quit if they do)
-                            Err(TryLockError::Poisoned(_)) => {
-                                let report = Report::msg("program data mutex poisoned")
-                                    .note("another thread panicked while holding the lock")
-                                    .suggestion("the error did not occur here (and has nothing to do with here), check the other threads and their logs")
-                                    .wrap_err("could not lock program data mutex")
-                                    .wrap_err("could not obtain program data");
-                                error!(target: DOMINO_EFFECT_FAILURE, ?report);
-                                event_loop_return!(Err(report));
-                            }
-                            Err(TryLockError::WouldBlock) => {
-                                trace!(target: THREAD_TRACE_MUTEX_SYNC, "mutex locked, waiting and retrying");
-                                sleep(MUTEX_LOCK_RETRY_DELAY);
-                                continue;
-                            }
-                            Ok(data) => {
-                                trace!(target: THREAD_TRACE_MUTEX_SYNC, ?data, "obtained program data");
-                                break data;
-                            }
-                        }
-                    };
-                    span_obtain_data.record("tries", tries);
-                    span_obtain_data.record("time_taken_to_obtain", tracing::field::debug(Instant::now() - start));
-                    span_obtain_data.exit();
-
-                    program_data
-                };
+                let (program_data, LockTimings { time_taken_to_obtain, .. }) = obtain_program_data(&program_data_wrapped);
+                let original_ui_data = program_data.ui_data;
+                let engine_data = program_data.engine_data;
+                let mut ui_data = original_ui_data;
 
                 // Makes it easier to separate out frames
                 // Add 1 to the frame count, since "technically" we're in the previous frame, as we haven't started the next one yet (call `new_frame()`)
@@ -194,15 +195,30 @@ pub(crate) fn ui_thread(
                     &mut display,
                     &mut imgui_context,
                     &mut platform,
-                    &mut renderer,
+                    renderer.as_mut(),
                     &mut managers,
-                    &mut program_data.ui_data,
+                    &mut ui_data,
+                    engine_data,
                     &message_sender,
                     &message_receiver,
+                    time_taken_to_obtain,
                 );
 
                 trace!(target: UI_TRACE_RENDER, "{0} END RENDER FRAME {frame} {0}", str::repeat("=", 50), frame = imgui_context.frame_count());
 
+                // Only need to lock again if the UI actually changed something
+                if ui_data != original_ui_data {
+                    trace_span!(target: THREAD_TRACE_MUTEX_SYNC, "write_back_ui_data", ?ui_data).in_scope(|| {
+                        let mut program_data = program_data_wrapped.lock();
+                        program_data.ui_data = ui_data;
+                        MutexGuard::unlock_fair(program_data);
+                    });
+                }
+                if ui_data.windows != original_ui_data.windows {
+                    debug!(target: UI_DEBUG_GENERAL, windows = ?ui_data.windows, "open windows changed, saving to config");
+                    update_config(|config| config.runtime.ui.open_windows = ui_data.windows.to_config());
+                }
+
                 if let Err(error) = render_frame_result {
                     let error = error.wrap_err("errored while rendering frame").note("the program should exit");
                     error!(target: GENERAL_ERROR_FATAL, ?error);
@@ -219,32 +235,26 @@ pub(crate) fn ui_thread(
             } => {
                 // Here, we don't actually want to close the window, but inform the main thread that we'd like to quit
                 // Then, we wait for the main thread to tell us to quit
+                // If there are unsaved config changes, the user is asked what to do with them first, and can cancel quitting
                 let span_close_requested = debug_span!(target: UI_DEBUG_USER_INTERACTION, "close_requested").entered();
 
-                let message = Program(QuitAppNoError(QuitInteractionByUser));
-                debug_span!(target: THREAD_DEBUG_MESSAGE_SEND, "send_quit_signal", ?message).in_scope(|| {
-                    match message_sender.try_send(message) {
-                        Ok(()) => {
-                            // We have signalled the thread, wait till the next loop when the main thread wants us to exit
-                            debug!(target: THREAD_DEBUG_MESSAGE_SEND, "program thread signalled, should exit soon");
-                            debug!(target: UI_DEBUG_GENERAL, "see you on the other side!");
-                        }
-
-                        // Neither of these errors should happen ever, but better to be safe
-                        Err(Disconnected(_failed_message)) => {
-                            event_loop_return!(Err(error_send_never_should_be_disconnected()));
-                        }
-                        Err(Full(_failed_message)) => {
-                            event_loop_return!(Err(error_never_should_be_full()));
-                        }
-                    }
-                });
+                // The config gets saved when the app exits, so this is our last chance to remember where the window was
+                save_window_geometry(display.gl_window().window());
+
+                if let Err(report) = request_quit(&message_sender) {
+                    event_loop_return!(Err(report.wrap_err("could not request quit")));
+                }
+                // Redraw straight away even when power saving, in case the unsaved changes popup needs to be shown
+                input_since_redraw = true;
                 span_close_requested.exit();
             }
 
             //Catch-all, passes onto the glutin backend
             event => {
                 let span_event_passthrough = trace_span!(target: UI_TRACE_EVENT_LOOP, "event_passthrough").entered();
+                if let glutin::event::Event::WindowEvent { .. } = event {
+                    input_since_redraw = true;
+                }
                 let gl_window = display.gl_window();
                 platform.handle_event(imgui_context.io_mut(), gl_window.window(), &event);
                 span_event_passthrough.exit();
@@ -272,6 +282,36 @@ pub(crate) fn ui_thread(
     Ok(())
 }
 
+/// How long the UI thread had to wait for the [ProgramData] lock, and how long it then held it for
+#[derive(Copy, Clone, Debug)]
+struct LockTimings {
+    time_taken_to_obtain: Duration,
+    time_held: Duration,
+}
+
+/// Copies out the [ProgramData], only holding the lock long enough to do so, so the engine isn't blocked for the whole frame
+fn obtain_program_data(program_data_wrapped: &Mutex<ProgramData>) -> (ProgramData, LockTimings) {
+    let span_obtain_data = trace_span!(target: THREAD_TRACE_MUTEX_SYNC, "obtain_data", time_taken_to_obtain = Empty, time_held = Empty).entered();
+
+    let start = Instant::now();
+    // [parking_lot]'s mutex parks the thread while it waits instead of us spinning on `try_lock()`, and is eventually fair so the engine can't starve us
+    // It also can't be poisoned, so there's no need to handle another thread panicking here (the program thread handles that)
+    let program_data = program_data_wrapped.lock();
+    trace!(target: THREAD_TRACE_MUTEX_SYNC, data = ?*program_data, "obtained program data");
+    let time_taken_to_obtain = Instant::now() - start;
+    span_obtain_data.record("time_taken_to_obtain", debug(time_taken_to_obtain));
+    let lock_obtained = Instant::now();
+
+    let data = *program_data;
+    // Give the lock back as soon as we're done with it, and hand it over fairly so the other threads get their turn
+    MutexGuard::unlock_fair(program_data);
+    let time_held = Instant::now() - lock_obtained;
+    span_obtain_data.record("time_held", debug(time_held));
+    span_obtain_data.exit();
+
+    (data, LockTimings { time_taken_to_obtain, time_held })
+}
+
 /// Called every frame, handles everything required to draw an entire frame
 ///
 /// Sets up all the boilerplate, then calls [inner_render] to create (build) the actual ui
@@ -280,11 +320,13 @@ fn outer_render_a_frame(
     display: &mut Display,
     imgui_context: &mut Context,
     platform: &mut WinitPlatform,
-    renderer: &mut Renderer,
+    renderer: &mut dyn UiRenderer,
     managers: &mut UiManagers,
     ui_data: &mut UiData,
+    engine_data: EngineData,
     message_sender: &BroadcastSender<ThreadMessage>,
     message_receiver: &BroadcastReceiver<ThreadMessage>,
+    time_taken_to_obtain_data: Duration,
 ) -> FallibleFn {
     let span_outer_render = trace_span!(
         target: UI_TRACE_RENDER,
@@ -294,7 +336,28 @@ fn outer_render_a_frame(
     )
     .entered();
     let start_outer_render = Instant::now();
+    // Only record the phase timings if they're being displayed, since it's wasted effort otherwise
+    // [FrameInfo::render()] sets this back to `true` each frame that they're displayed
+    let record_phase_timings = std::mem::take(&mut managers.frame_info.phase_timings_visible);
+
+    trace_span!(target: UI_TRACE_RENDER, "update_window_status").in_scope(|| update_window_status(display.gl_window().window(), managers, engine_data.status));
+
+    trace_span!(target: UI_TRACE_RENDER, "poll_resource_watcher").in_scope(|| {
+        for kind in managers.resource_watcher.poll() {
+            match kind {
+                ResourceKind::Fonts => {
+                    if let Err(report) = managers.font_manager.reload_list_from_resources() {
+                        let report = report.wrap_err("could not hot-reload fonts");
+                        warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report));
+                    }
+                }
+                // Nothing else is loaded by the UI
+                _ => trace!(target: UI_TRACE_RENDER, ?kind, "no need to hot-reload resource"),
+            }
+        }
+    });
 
+    let start_maybe_rebuild_font = Instant::now();
     trace_span!(target: UI_TRACE_RENDER, "maybe_rebuild_font").in_scope(|| {
         let fonts = imgui_context.fonts();
         match managers.font_manager.rebuild_font_if_needed(fonts) {
@@ -308,10 +371,7 @@ fn outer_render_a_frame(
                 let result = renderer.reload_font_texture(imgui_context);
                 match result {
                     Ok(()) => trace!(target: UI_TRACE_RENDER, "renderer font texture reloaded successfully"),
-                    Err(err) => {
-                        let report = Report::new(err).wrap_err("renderer could not reload font texture");
-                        warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report));
-                    }
+                    Err(report) => warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report)),
                 }
             }
             Ok(false) => {
@@ -320,11 +380,14 @@ fn outer_render_a_frame(
         }
     });
 
+    let time_to_maybe_rebuild_font = Instant::now() - start_maybe_rebuild_font;
+
     // Create a new imgui frame to render to
     trace!(target: UI_TRACE_RENDER, "new_frame()");
     let ui = imgui_context.new_frame();
     trace!(target: UI_TRACE_RENDER, new_frame=?ui);
     //Build the UI
+    let start_outer_build_ui = Instant::now();
     {
         let span_outer_build_ui = trace_span!(target: UI_TRACE_BUILD_INTERFACE, "outer_build_ui").entered();
         // Try to set our custom font
@@ -378,9 +441,18 @@ fn outer_render_a_frame(
         //TODO: Remove unneeded docking code
         trace!(target: UI_TRACE_BUILD_INTERFACE, "build docking area");
         let docking_area = UiDockingArea {};
-        let _dock_node = docking_area.dockspace("Main Dock Area");
+        // Leave space at the bottom of the main window for the status bar
+        let status_bar_height = status_bar_height(ui);
+        let dockspace_size = [main_window_size[0], main_window_size[1] - status_bar_height];
+        // Has to happen before the dockspace is submitted, so it uses the new layout straight away
+        if std::mem::take(&mut managers.apply_default_layout) {
+            debug!(target: UI_DEBUG_GENERAL, "applying default dock layout");
+            docking_area.build_default_layout(MAIN_DOCK_AREA_NAME, dockspace_size, window_title);
+        }
+        let _dock_node = docking_area.dockspace(MAIN_DOCK_AREA_NAME, [0.0, -status_bar_height]);
+        render_status_bar(ui, managers, ui_data, engine_data);
 
-        build_ui(ui, managers, ui_data, message_sender, message_receiver).wrap_err("building ui failed")?;
+        build_ui(ui, managers, ui_data, engine_data.status, message_sender, message_receiver).wrap_err("building ui failed")?;
 
         // Technically we should only build the UI if [maybe_window_token] is [Some] ([None] means the window is hidden)
         // The window should never be hidden though, so this is a non-issue and we ignore it
@@ -403,31 +475,47 @@ fn outer_render_a_frame(
 
         span_outer_build_ui.exit();
     }
+    let time_to_outer_build_ui = Instant::now() - start_outer_build_ui;
 
     // Start drawing to our OpenGL context (via glium/glutin)
+    let start_draw_frame = Instant::now();
     {
         let span_draw_frame = trace_span!(target: UI_TRACE_RENDER, "draw_frame").entered();
-        let gl_window = display.gl_window();
-        trace!(target: UI_TRACE_RENDER, "start drawing frame to backbuffer: `display.draw()`");
-        let mut target = display.draw();
-        trace!(target: UI_TRACE_RENDER, "clearing buffer: `target.clear_color_srgb()`");
-        target.clear_color_srgb(0.0, 0.0, 0.0, 0.0); //Clear background so we don't get any leftovers from previous frames
-
         // Render our imgui frame now we've written to it
         trace!(target: UI_TRACE_RENDER, "preparing platform for render: `platform.prepare_render()`");
-        platform.prepare_render(ui, gl_window.window());
+        platform.prepare_render(ui, display.gl_window().window());
         trace!(target: UI_TRACE_RENDER, "rendering imgui frame: `imgui_context.render()`");
         let draw_data = imgui_context.render();
 
-        trace!(target: UI_TRACE_RENDER, "gl render: `renderer.render()`");
-        renderer.render(&mut target, draw_data).wrap_err("could not render draw data")?;
-        trace!(target: UI_TRACE_RENDER, "swapping buffers: `target.finish()`");
-        target.finish().wrap_err("failed to swap buffers")?;
+        renderer.render(display, draw_data)?;
 
         trace!(target: UI_TRACE_RENDER, "render complete");
 
+        // This is done straight after the buffers are swapped, so reading back the frame doesn't stall the pipeline more than it has to
+        // One-shot, so clear the flag even if saving fails, otherwise we'd keep trying (and failing) every frame
+        if std::mem::take(&mut managers.screenshot_requested) {
+            match save_screenshot(display) {
+                Ok(path) => {
+                    info!(target: UI_DEBUG_GENERAL, ?path, "saved screenshot");
+                    managers.toast = Some(Toast::new(format!("Saved screenshot to {}", path.display()), false));
+                }
+                Err(report) => an_error_occurred(report.wrap_err("could not save screenshot")),
+            }
+        }
+
         span_draw_frame.exit();
     }
+    let time_to_draw_frame = Instant::now() - start_draw_frame;
+
+    if record_phase_timings {
+        managers.frame_info.record_phase_timings(FramePhaseTimings {
+            obtain_data: time_taken_to_obtain_data.as_secs_f32() * 1000.0,
+            maybe_rebuild_font: time_to_maybe_rebuild_font.as_secs_f32() * 1000.0,
+            outer_build_ui: time_to_outer_build_ui.as_secs_f32() * 1000.0,
+            draw_frame: time_to_draw_frame.as_secs_f32() * 1000.0,
+            ..FramePhaseTimings::default() // The frame is filled in when they're recorded
+        });
+    }
 
     span_outer_render.record("time_to_render", debug(Instant::now() - start_outer_render));
     span_outer_render.exit();
@@ -476,6 +564,130 @@ fn process_messages_with_return(_message_sender: &BroadcastSender<ThreadMessage>
 ///Initialises the UI system and returns it
 ///
 /// * `title` - Title of the created window
+/// Works out where the window should be positioned when it's created, from the [start_monitor](crate::config::init_time::ui_config::UiConfig::start_monitor) and [start_position](crate::config::init_time::ui_config::UiConfig::start_position) config options
+///
+/// Returns [None] if the OS should choose
+fn initial_window_position(event_loop: &EventLoop<()>, start_monitor: Option<usize>, start_position: Option<[i32; 2]>) -> Option<PhysicalPosition<i32>> {
+    let offset = start_position.map_or(PhysicalPosition::new(0, 0), |[x, y]| PhysicalPosition::new(x, y));
+    let monitor_index = match start_monitor {
+        // No monitor, so the position (if any) is relative to the desktop
+        None => return start_position.map(|_| offset),
+        Some(index) => index,
+    };
+
+    let monitors: Vec<MonitorHandle> = event_loop.available_monitors().collect();
+    debug!(target: UI_DEBUG_GENERAL, ?monitors, "available monitors");
+    let monitor = match monitors.get(monitor_index) {
+        Some(monitor) => monitor.clone(),
+        None => {
+            warn!(
+                target: GENERAL_WARNING_NON_FATAL,
+                monitor_index,
+                num_monitors = monitors.len(),
+                "configured start monitor doesn't exist, falling back to primary monitor"
+            );
+            event_loop.primary_monitor()?
+        }
+    };
+    let monitor_position = monitor.position();
+    debug!(target: UI_DEBUG_GENERAL, ?monitor, ?monitor_position, ?offset, "positioning window on monitor");
+    Some(PhysicalPosition::new(monitor_position.x + offset.x, monitor_position.y + offset.y))
+}
+
+/// The settings used to create the OpenGL context (see [glutin::ContextBuilder])
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct ContextSettings {
+    vsync: bool,
+    hardware_acceleration: Option<bool>,
+    srgb: bool,
+    multisampling: u16,
+}
+
+/// Creates the [Display], relaxing the context settings and trying again if it fails
+///
+/// Some combinations of settings aren't supported on every system (usually failing with [NoAvailablePixelFormat]),
+/// so each retry turns off another setting: first sRGB, then multisampling, then letting the system choose whether to use hardware acceleration.
+/// Returns the display along with the settings that worked
+fn create_display(window_builder: &WindowBuilder, requested: ContextSettings, event_loop: &EventLoop<()>) -> eyre::Result<(Display, ContextSettings)> {
+    let attempts = [
+        requested,
+        ContextSettings { srgb: false, ..requested },
+        ContextSettings {
+            srgb: false,
+            multisampling: 0,
+            ..requested
+        },
+        ContextSettings {
+            srgb: false,
+            multisampling: 0,
+            hardware_acceleration: None,
+            ..requested
+        },
+    ];
+
+    let mut failures = vec![];
+    // Some of the attempts might be the same as ones before (e.g. if sRGB was already off), so don't bother trying those again
+    for (index, settings) in attempts.into_iter().enumerate() {
+        if attempts[..index].contains(&settings) {
+            continue;
+        }
+        let context_builder = glutin::ContextBuilder::new()
+            .with_vsync(settings.vsync)
+            .with_hardware_acceleration(settings.hardware_acceleration)
+            .with_srgb(settings.srgb)
+            .with_multisampling(settings.multisampling);
+        debug!(target: UI_DEBUG_GENERAL, attempt = index + 1, ?settings, "trying to create display");
+        match Display::new(window_builder.clone(), context_builder, event_loop) {
+            Ok(display) => return Ok((display, settings)),
+            Err(error) => {
+                warn!(target: GENERAL_WARNING_NON_FATAL, attempt = index + 1, ?settings, %error, "could not create display");
+                failures.push(format!("{settings:?}: {error}"));
+            }
+        }
+    }
+
+    Err(Report::msg("could not initialise display"))
+        .section(failures.join("\n").header("Attempted context settings:"))
+        .note(format!("[NoAvailablePixelFormat] (`{NoAvailablePixelFormat}`) means the context settings aren't a compatible combination on your system"))
+        .suggestion("try changing the vsync, hardware acceleration, srgb and multisampling settings in the init config")
+}
+
+/// Stores the window's current position, size and maximised state in the init config, so that they're restored the next time the app starts
+///
+/// If the window is maximised, only that is saved (so the un-maximised geometry from before is kept), and nothing is saved if the window is minimised
+fn save_window_geometry(window: &Window) {
+    let maximised = window.is_maximized();
+    let size = window.inner_size();
+    let position = match window.outer_position() {
+        Ok(position) => Some(position),
+        Err(error) => {
+            trace!(target: UI_DEBUG_GENERAL, ?error, "could not get window position, not saving it");
+            None
+        }
+    };
+    debug!(target: UI_DEBUG_GENERAL, maximised, ?size, ?position, "saving window geometry");
+
+    // Minimised windows have a zero size (on some platforms), and restoring to that would make the window invisible
+    if size.width == 0 || size.height == 0 {
+        debug!(target: UI_DEBUG_GENERAL, "window is minimised or has zero size, not saving geometry");
+        return;
+    }
+
+    update_config(|config| {
+        let ui_config = &mut config.init.ui_config;
+        ui_config.start_maximised = maximised;
+        if maximised {
+            return;
+        }
+        ui_config.window_size = Some([size.width, size.height]);
+        if let Some(position) = position {
+            // The position is absolute, so it's not relative to a monitor any more
+            ui_config.start_monitor = None;
+            ui_config.start_position = Some([position.x, position.y]);
+        }
+    });
+}
+
 fn init_ui_system(title: &str) -> eyre::Result<UiSystem> {
     let span_init_ui = debug_span!(target: UI_DEBUG_GENERAL, "init_ui").entered();
 
@@ -495,26 +707,38 @@ fn init_ui_system(title: &str) -> eyre::Result<UiSystem> {
     event_loop = EventLoopBuilder::with_any_thread(&mut EventLoopBuilder::new(), true).build();
     debug!(target: UI_DEBUG_GENERAL, ?event_loop, "[winit] event loop created");
 
-    debug!(target: UI_DEBUG_GENERAL, "creating [glutin] context builder");
-    let glutin_context_builder = glutin::ContextBuilder::new() //TODO: Configure
-        .with_vsync(config.vsync)
-        .with_hardware_acceleration(config.hardware_acceleration)
-        .with_srgb(true)
-        .with_multisampling(config.multisampling);
-    debug!(target: UI_DEBUG_GENERAL, ?glutin_context_builder, "created [glutin] context builder");
-
     debug!(target: UI_DEBUG_GENERAL, "creating [winit] window builder");
-    let window_builder = WindowBuilder::new().with_title(title).with_maximized(config.start_maximised);
+    let mut window_builder = WindowBuilder::new().with_title(title).with_maximized(config.start_maximised);
+    if let Some(position) = initial_window_position(&event_loop, config.start_monitor, config.start_position) {
+        window_builder = window_builder.with_position(position);
+    }
+    if let Some([width, height]) = config.window_size {
+        window_builder = window_builder.with_inner_size(PhysicalSize::new(width, height));
+    }
     debug!(target: UI_DEBUG_GENERAL, ?window_builder, "created [winit] window builder");
-    //TODO: Configure
     debug!(target: UI_DEBUG_GENERAL, "creating [glium] display");
-    let gl_display: Display = Display::new(window_builder, glutin_context_builder, &event_loop)
-        .wrap_err("could not initialise display")
-        .note(format!(
-        "if the error is [NoAvailablePixelFormat] (`{}`), try checking the [glutin::ContextBuilder] settings: vsync, hardware acceleration and srgb may not be a compatible combination on your system",
-        NoAvailablePixelFormat
-    ))?;
-    debug!(target: UI_DEBUG_GENERAL, display=?gl_display, "created [glium] display");
+    let requested_settings = ContextSettings {
+        vsync: config.vsync,
+        hardware_acceleration: config.hardware_acceleration,
+        srgb: config.srgb,
+        multisampling: config.multisampling,
+    };
+    let (gl_display, context_settings) = create_display(&window_builder, requested_settings, &event_loop)?;
+    debug!(target: UI_DEBUG_GENERAL, display=?gl_display, ?context_settings, "created [glium] display");
+    if context_settings != requested_settings {
+        warn!(
+            target: GENERAL_WARNING_NON_FATAL,
+            ?requested_settings,
+            ?context_settings,
+            "could not create display with the configured context settings, had to relax them. saving the settings that worked"
+        );
+        update_config(|config| {
+            let ui_config = &mut config.init.ui_config;
+            ui_config.hardware_acceleration = context_settings.hardware_acceleration;
+            ui_config.srgb = context_settings.srgb;
+            ui_config.multisampling = context_settings.multisampling;
+        });
+    }
 
     debug!(target: UI_DEBUG_GENERAL, "creating [imgui] context");
     imgui_context = Context::create();
@@ -524,6 +748,34 @@ fn init_ui_system(title: &str) -> eyre::Result<UiSystem> {
     imgui_context.io_mut().config_flags |= imgui::ConfigFlags::DOCKING_ENABLE;
     debug!(target: UI_DEBUG_GENERAL, config_flags=?imgui_context.io().config_flags);
 
+    // Without this imgui would put its settings in the working directory, which changes depending on how the app is run
+    debug!(target: UI_DEBUG_GENERAL, "setting [imgui] settings and log file paths");
+    match get_main_resource_folder_path() {
+        Ok(resources_path) => {
+            let (ini_path, log_path) = read_config_value(|config| (config.runtime.resources.imgui_settings_path.clone(), config.runtime.resources.imgui_log_path.clone()));
+            let (ini_path, log_path) = (resources_path.join(ini_path), resources_path.join(log_path));
+            // The file not existing is fine (imgui creates it when it first saves), but the folder it's in has to exist
+            for path in [&ini_path, &log_path] {
+                if let Some(parent) = path.parent() {
+                    if let Err(error) = fs::create_dir_all(parent) {
+                        warn!(target: GENERAL_WARNING_NON_FATAL, ?error, ?parent, "could not create folder for imgui file");
+                    }
+                }
+            }
+            debug!(target: UI_DEBUG_GENERAL, ?ini_path, ?log_path);
+            imgui_context.set_ini_filename(ini_path);
+            imgui_context.set_log_filename(log_path);
+        }
+        Err(report) => {
+            let report = report.wrap_err("could not get resources folder for imgui settings").note("imgui will use its default settings file path");
+            warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report));
+        }
+    }
+
+    // If there's no saved layout (e.g. first run), the windows would all be floating on top of each other, so give them a sensible layout instead
+    let has_saved_layout = imgui_context.ini_filename().map_or(false, |path| path.exists());
+    debug!(target: UI_DEBUG_GENERAL, has_saved_layout);
+
     let font_manager = debug_span!(target: UI_DEBUG_GENERAL, "create_font_manager").in_scope(|| {
         let mut font_manager = FontManager::new().wrap_err("failed to create font manager")?;
         debug!(target: UI_DEBUG_GENERAL, "loading font manager fonts list"); //Need to call it now or else we don't have any fonts loaded and the manager craps itself later
@@ -532,18 +784,19 @@ fn init_ui_system(title: &str) -> eyre::Result<UiSystem> {
         eyre::Result::<FontManager>::Ok(font_manager)
     })?;
 
-    //TODO: High DPI setting
     debug!(target: UI_DEBUG_GENERAL, "creating [winit] platform");
     platform = WinitPlatform::init(&mut imgui_context);
     debug!(target: UI_DEBUG_GENERAL, ?platform, "created [winit] platform");
 
     debug!(target: UI_DEBUG_GENERAL, "attaching window to platform");
-    platform.attach_window(imgui_context.io_mut(), gl_display.gl_window().window(), HiDpiMode::Default);
+    let hidpi_mode = read_config_value(|config| config.init.ui_config.hidpi_mode).to_hidpi_mode();
+    debug!(target: UI_DEBUG_GENERAL, ?hidpi_mode);
+    // The platform takes care of scaling the framebuffer size and mouse coordinates to match the mode, so nothing else needs to know about it
+    platform.attach_window(imgui_context.io_mut(), gl_display.gl_window().window(), hidpi_mode);
     debug!(target: UI_DEBUG_GENERAL, "attached window to platform");
 
-    debug!(target: UI_DEBUG_GENERAL, "creating [glium] renderer");
-    renderer = Renderer::init(&mut imgui_context, &gl_display).wrap_err("failed to create renderer")?;
-    debug!(target: UI_DEBUG_GENERAL, "created [glium] renderer");
+    let backend = read_config_value(|config| config.init.ui_config.backend);
+    renderer = create_renderer(backend, &mut imgui_context, &gl_display)?;
 
     debug_span!(target: UI_DEBUG_GENERAL, "clipboard_init").in_scope(|| match clipboard_integration::clipboard_init() {
         Ok(clipboard_backend) => {
@@ -570,6 +823,12 @@ fn init_ui_system(title: &str) -> eyre::Result<UiSystem> {
         managers: UiManagers {
             font_manager,
             frame_info: FrameInfo::new(),
+            resource_watcher: ResourceWatcher::new(&[ResourceKind::Fonts]),
+            screenshot_requested: false,
+            apply_default_layout: !has_saved_layout,
+            toast: None,
+            window_title: String::new(),
+            taskbar_progress: TaskbarProgress::new(),
         },
     })
 }
@@ -595,6 +854,12 @@ fn apply_theme_style_colours<'ui>(ui: &'ui imgui::Ui, theme: &Theme) -> Vec<Colo
     // Text
     vec.push(ui.push_style_color(StyleColor::Text, theme.text.normal));
 
+    // Backgrounds
+    vec.push(ui.push_style_color(StyleColor::WindowBg, theme.text.background));
+    vec.push(ui.push_style_color(StyleColor::PopupBg, theme.text.background));
+    vec.push(ui.push_style_color(StyleColor::MenuBarBg, modify_col(theme.text.background, 1.0, 1.5, 1.0)));
+    vec.push(ui.push_style_color(StyleColor::TitleBg, modify_col(theme.text.background, 1.0, 0.7, 1.0)));
+
     //TODO: Share these a little more if possible
 
     // Frames
@@ -648,3 +913,101 @@ fn apply_theme_style_colours<'ui>(ui: &'ui imgui::Ui, theme: &Theme) -> Vec<Colo
     vec.push(ui.push_style_color(StyleColor::Border, modify_col(theme.text.accent, 0.14, 0.5, 0.5)));
     vec
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::EngineStatus;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::thread;
+
+    fn test_program_data() -> ProgramData {
+        ProgramData {
+            ui_data: UiData::default(),
+            engine_data: EngineData {
+                status: EngineStatus::Paused,
+                iterations: 1234,
+            },
+        }
+    }
+
+    /// Much longer than getting the lock should ever take, so the tests don't depend on how the threads get scheduled
+    const MAX_WAIT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn obtain_waits_for_long_held_lock() {
+        let program_data_wrapped = Mutex::new(test_program_data());
+        let locked = Barrier::new(2);
+        let released = AtomicBool::new(false);
+
+        let (program_data, timings) = thread::scope(|scope| {
+            scope.spawn(|| {
+                let mut guard = program_data_wrapped.lock();
+                locked.wait();
+                thread::sleep(Duration::from_millis(100));
+                guard.engine_data.iterations += 1;
+                released.store(true, Relaxed);
+                MutexGuard::unlock_fair(guard);
+            });
+            locked.wait();
+            obtain_program_data(&program_data_wrapped)
+        });
+
+        // We can only have got the lock once the other thread was done with it, so we should see its change
+        assert!(released.load(Relaxed));
+        assert_eq!(program_data.engine_data.iterations, test_program_data().engine_data.iterations + 1);
+        assert!(timings.time_taken_to_obtain < MAX_WAIT, "waited {:?} for the lock", timings.time_taken_to_obtain);
+        assert!(timings.time_held < MAX_WAIT, "held the lock for {:?}", timings.time_held);
+    }
+
+    #[test]
+    fn obtain_isnt_starved_by_hogging_thread() {
+        const FRAMES: usize = 100;
+        let program_data_wrapped = Mutex::new(test_program_data());
+        let started = Barrier::new(2);
+        let done = AtomicBool::new(false);
+
+        let observed = thread::scope(|scope| {
+            // Pretends to be an engine that immediately re-locks, without ever handing the lock over fairly
+            scope.spawn(|| {
+                // Already holding the lock when we start, so we can't get through all our frames before this thread gets going
+                let mut guard = program_data_wrapped.lock();
+                started.wait();
+                loop {
+                    guard.engine_data.iterations += 1;
+                    thread::sleep(Duration::from_micros(100));
+                    drop(guard);
+                    if done.load(Relaxed) {
+                        break;
+                    }
+                    guard = program_data_wrapped.lock();
+                }
+            });
+
+            started.wait();
+            let observed = (0..FRAMES)
+                .map(|_| {
+                    let obtained = obtain_program_data(&program_data_wrapped);
+                    // Pretend to render the frame, like the real UI does between obtaining the data
+                    thread::sleep(Duration::from_millis(1));
+                    obtained
+                })
+                .collect::<Vec<_>>();
+            // Stop the other thread before checking anything, otherwise a failed assert would leave it running and the scope would never end
+            done.store(true, Relaxed);
+            observed
+        });
+
+        for (_, timings) in &observed {
+            assert!(timings.time_taken_to_obtain < MAX_WAIT, "waited {:?} for the lock", timings.time_taken_to_obtain);
+            assert!(timings.time_held < MAX_WAIT, "held the lock for {:?}", timings.time_held);
+        }
+        let iterations = observed.iter().map(|(program_data, _)| program_data.engine_data.iterations).collect::<Vec<_>>();
+        assert!(iterations.windows(2).all(|pair| pair[0] <= pair[1]), "iterations went backwards: {iterations:?}");
+        // Every time the iterations changed between our frames, the lock was handed over to the other thread and back again
+        let handovers = iterations.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        assert!(handovers > 0, "the other thread never got the lock between our frames");
+    }
+}
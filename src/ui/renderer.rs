@@ -0,0 +1,61 @@
+//! Abstraction over the renderer that draws the imgui draw data to the screen
+//!
+//! Currently only [glium](imgui_glium_renderer) is supported, but having the renderer behind [UiRenderer] means other backends (e.g. glow)
+//! can be added later without touching the main render loop
+use crate::config::init_time::ui_config::Backend;
+use crate::helper::logging::event_targets::*;
+use crate::FallibleFn;
+use color_eyre::eyre::WrapErr;
+use color_eyre::{eyre, Report};
+use glium::{Display, Surface};
+use imgui::{Context, DrawData};
+use imgui_glium_renderer::Renderer as GliumRenderer;
+use tracing::{debug, trace};
+
+/// A renderer that can draw imgui's [DrawData] to the screen
+pub(in crate::ui) trait UiRenderer {
+    /// Creates the renderer, uploading the font atlas from the [Context]
+    fn init(imgui_context: &mut Context, display: &Display) -> eyre::Result<Self>
+    where
+        Self: Sized;
+
+    /// Draws a whole frame (clearing the screen, rendering the `draw_data` and swapping the buffers)
+    fn render(&mut self, display: &Display, draw_data: &DrawData) -> FallibleFn;
+
+    /// Re-uploads the font atlas texture. Must be called whenever the font atlas is rebuilt
+    fn reload_font_texture(&mut self, imgui_context: &mut Context) -> FallibleFn;
+}
+
+/// Creates the renderer for the given [Backend]
+pub(in crate::ui) fn create_renderer(backend: Backend, imgui_context: &mut Context, display: &Display) -> eyre::Result<Box<dyn UiRenderer>> {
+    debug!(target: UI_DEBUG_GENERAL, ?backend, "creating renderer");
+    let renderer: Box<dyn UiRenderer> = match backend {
+        Backend::Glium => Box::new(<GliumRenderer as UiRenderer>::init(imgui_context, display).wrap_err("failed to create [glium] renderer")?),
+    };
+    debug!(target: UI_DEBUG_GENERAL, ?backend, "created renderer");
+    Ok(renderer)
+}
+
+impl UiRenderer for GliumRenderer {
+    fn init(imgui_context: &mut Context, display: &Display) -> eyre::Result<Self> {
+        GliumRenderer::init(imgui_context, display).map_err(Report::new)
+    }
+
+    fn render(&mut self, display: &Display, draw_data: &DrawData) -> FallibleFn {
+        trace!(target: UI_TRACE_RENDER, "start drawing frame to backbuffer: `display.draw()`");
+        let mut target = display.draw();
+        trace!(target: UI_TRACE_RENDER, "clearing buffer: `target.clear_color_srgb()`");
+        target.clear_color_srgb(0.0, 0.0, 0.0, 0.0); //Clear background so we don't get any leftovers from previous frames
+
+        trace!(target: UI_TRACE_RENDER, "gl render: `renderer.render()`");
+        // Make sure the frame is always finished, even if rendering failed, otherwise glium panics when the frame is dropped
+        let render_result = GliumRenderer::render(self, &mut target, draw_data).wrap_err("could not render draw data");
+        trace!(target: UI_TRACE_RENDER, "swapping buffers: `target.finish()`");
+        let finish_result = target.finish().wrap_err("failed to swap buffers");
+        render_result.and(finish_result)
+    }
+
+    fn reload_font_texture(&mut self, imgui_context: &mut Context) -> FallibleFn {
+        GliumRenderer::reload_font_texture(self, imgui_context).wrap_err("renderer could not reload font texture")
+    }
+}
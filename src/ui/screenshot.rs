@@ -0,0 +1,49 @@
+//! Saving screenshots of the whole UI window, for bug reports
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::WrapErr;
+use color_eyre::{eyre, Help};
+use glium::texture::RawImage2d;
+use glium::Display;
+use tracing::trace;
+
+use crate::helper::logging::event_targets::*;
+use crate::resources::resource_manager::{resource_folder_path, ResourceKind};
+
+/// Reads back the frame that was just presented, and saves it as a PNG in the screenshots folder, returning the path of the file
+///
+/// Must be called *after* the buffers have been swapped, since it reads the front buffer
+pub(in crate::ui) fn save_screenshot(display: &Display) -> eyre::Result<PathBuf> {
+    trace!(target: UI_TRACE_RENDER, "reading front buffer: `display.read_front_buffer()`");
+    let image: RawImage2d<u8> = display.read_front_buffer().wrap_err("could not read the front buffer")?;
+    let (width, height) = (image.width, image.height);
+    let row_length = width as usize * 4;
+
+    // OpenGL's rows go bottom to top, but PNGs go top to bottom
+    let flipped: Vec<u8> = image.data.chunks_exact(row_length).rev().flatten().copied().collect();
+
+    let screenshots_folder = resource_folder_path(ResourceKind::Screenshots)?;
+    fs::create_dir_all(&screenshots_folder)
+        .wrap_err("could not create screenshots folder")
+        .with_note(|| format!("screenshots folder: {}", screenshots_folder.display()))?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let path = screenshots_folder.join(format!("screenshot_{}_{:03}.png", timestamp.as_secs(), timestamp.subsec_millis()));
+
+    trace!(target: UI_TRACE_RENDER, ?path, width, height, "writing screenshot");
+    let file = File::create(&path)
+        .wrap_err("could not create screenshot file")
+        .with_note(|| format!("file path: {}", path.display()))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&flipped))
+        .wrap_err("could not encode screenshot")
+        .with_note(|| format!("file path: {}", path.display()))?;
+    Ok(path)
+}
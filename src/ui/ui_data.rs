@@ -1,26 +1,100 @@
+use crate::config::read_config_value;
+use crate::helper::logging::event_targets::*;
+use std::collections::BTreeMap;
+use tracing::{debug, warn};
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct UiData {
-    pub windows: ShownWindows,
+    pub windows: WindowRegistry,
 }
 
+/// The windows that can be shown/hidden by the user
+///
+/// Everything else about each window (title, keybinding, how to render it) is registered in [WINDOWS](crate::ui::build_ui_impl::window_registry::WINDOWS).
+/// Only whether the window is open is stored here, so that [UiData] stays [Copy]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
-pub struct ShownWindows {
-    pub show_demo_window: bool,
-    pub show_metrics_window: bool,
-    pub show_ui_management_window: bool,
-    pub show_config_window: bool,
+pub enum WindowId {
+    Demo,
+    Metrics,
+    Config,
+    UiManagement,
+    ErrorHistory,
+    Help,
+}
+
+impl WindowId {
+    /// How many windows there are
+    pub const COUNT: usize = 6;
+    /// Every window, in the same order as their discriminants
+    pub const ALL: [WindowId; WindowId::COUNT] = [WindowId::Demo, WindowId::Metrics, WindowId::Config, WindowId::UiManagement, WindowId::ErrorHistory, WindowId::Help];
+
+    /// Name the window is saved under in the [open_windows](crate::config::run_time::ui_config::UiConfig::open_windows) config.
+    /// Shouldn't be changed, or the saved state for the window is lost
+    pub fn config_key(self) -> &'static str {
+        match self {
+            WindowId::Demo => "demo",
+            WindowId::Metrics => "metrics",
+            WindowId::Config => "config",
+            WindowId::UiManagement => "ui_management",
+            WindowId::ErrorHistory => "error_history",
+            WindowId::Help => "help",
+        }
+    }
+}
+
+/// Stores whether each [WindowId] is open
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct WindowRegistry {
+    open: [bool; WindowId::COUNT],
+}
+
+impl WindowRegistry {
+    /// Whether the window is currently open
+    pub fn is_open(&self, id: WindowId) -> bool {
+        self.open[id as usize]
+    }
+
+    /// Gets whether the window is open, so it can be changed (e.g. by the window's close button)
+    pub fn open_mut(&mut self, id: WindowId) -> &mut bool {
+        &mut self.open[id as usize]
+    }
+
+    /// Converts to the form that's saved in the config (see [config_key](WindowId::config_key))
+    pub fn to_config(&self) -> BTreeMap<String, bool> {
+        WindowId::ALL.iter().map(|&id| (id.config_key().to_owned(), self.is_open(id))).collect()
+    }
+
+    /// Applies the open/closed states saved in the config
+    ///
+    /// Windows that weren't saved keep their current state, and saved entries that don't match any window (e.g. a window that was removed) are ignored
+    fn apply_config(&mut self, saved: &BTreeMap<String, bool>) {
+        for (key, &open) in saved {
+            match WindowId::ALL.iter().find(|id| id.config_key() == key) {
+                Some(&id) => *self.open_mut(id) = open,
+                None => warn!(target: GENERAL_WARNING_NON_FATAL, key, "saved open state for unknown window, ignoring"),
+            }
+        }
+    }
+}
+
+impl UiData {
+    /// Creates the [UiData], restoring which windows were open from the config
+    pub fn from_config() -> Self {
+        let mut data = Self::default();
+        let saved = read_config_value(|config| config.runtime.ui.open_windows.clone());
+        debug!(target: UI_DEBUG_GENERAL, ?saved, "restoring open windows");
+        data.windows.apply_config(&saved);
+        data
+    }
 }
 
 impl Default for UiData {
     fn default() -> Self {
-        Self {
-            windows: ShownWindows {
-                show_demo_window: true,
-                show_metrics_window: true,
-                show_ui_management_window: true,
-                show_config_window: true,
-            },
+        let mut windows = WindowRegistry { open: [false; WindowId::COUNT] };
+        for id in [WindowId::Demo, WindowId::Metrics, WindowId::UiManagement, WindowId::Config] {
+            *windows.open_mut(id) = true;
         }
+        Self { windows }
     }
 }
 
@@ -1,9 +1,14 @@
 //! Module that contains the structs used in the [crate::ui] module
+use crate::config::read_config_value;
+use crate::helper::ring_buffer::RingBuffer;
+use crate::resources::resource_watcher::ResourceWatcher;
 use crate::ui::font_manager::FontManager;
+use crate::ui::renderer::UiRenderer;
+use crate::ui::window_status::TaskbarProgress;
 use glium::glutin::event_loop::EventLoop;
 use glium::Display;
+use std::time::Instant;
 use imgui::Context;
-use imgui_glium_renderer::Renderer;
 use imgui_winit_support::WinitPlatform;
 
 //TODO: Debug impls for these UI structs
@@ -19,53 +24,248 @@ pub(in crate::ui) struct UiBackend {
     pub imgui_context: Context,
     pub platform: WinitPlatform,
     /// The renderer that renders the current UI system
-    pub renderer: Renderer,
+    pub renderer: Box<dyn UiRenderer>,
 }
 
 #[derive(Debug, Clone)]
 pub(in crate::ui) struct UiManagers {
     pub font_manager: FontManager,
     pub frame_info: FrameInfo,
+    /// Watches for resources changing, so they can be hot-reloaded
+    pub resource_watcher: ResourceWatcher,
+    /// Set when the user asks for a screenshot. Cleared once the screenshot is taken (after the frame is drawn), so only one frame is saved
+    pub screenshot_requested: bool,
+    /// Set when the default dock layout should be (re)applied: on first run (when there's no saved layout), or when the user resets the layout.
+    /// Cleared once the layout has been built
+    pub apply_default_layout: bool,
+    /// Message that is briefly shown in the corner of the window, e.g. to confirm where a screenshot was saved
+    pub toast: Option<Toast>,
+    /// The title the OS window was last given, so it's only set again when it changes
+    pub window_title: String,
+    pub taskbar_progress: TaskbarProgress,
+}
+
+/// A short message shown in the bottom-right corner of the window for [TOAST_DURATION](crate::config::compile_time::ui_config::TOAST_DURATION), that doesn't need the user to do anything
+#[derive(Debug, Clone)]
+pub(in crate::ui) struct Toast {
+    pub message: String,
+    /// Whether the message is about something that failed, so it should be coloured as a warning
+    pub is_warning: bool,
+    /// When the toast was first shown
+    pub shown_at: Instant,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>, is_warning: bool) -> Self {
+        Self {
+            message: message.into(),
+            is_warning,
+            shown_at: Instant::now(),
+        }
+    }
 }
 
 /// Struct that stores arrays of floats for frame times (ΔT) and frame-rates
 ///
 ///
 /// # Performance Notes
-/// Although using a [Vec] as a FIFO queue normally would be a bad idea, since inserting at `[0]` always causes the entire vec to be shifted
-/// In benchmarks, it was actually *much* faster that using any other collection types:
-/// * [VecDeque] - Wouldn't work because in order to plot, a slice `[f32]` needs to be passed, and this is very tricky to get from a [VecDeque]
-/// * [SliceDeque] - Worked almost identically to [Vec], but was orders of magnitudes slower (`~1 us` for [SliceDeque] vs `~22ns` for [Vec], at 120 frames stored).
-/// At extreme frame counts (`~12000` frames), it did gain a slight advantage (`1us` vs `1.4us`),
-/// indicating that [SliceDeque] has `O(1)` performance, but has a massive overhead comparatively to [Vec]
+/// The values are stored in [RingBuffer]s, so pushing a frame is `O(1)` no matter how many frames are tracked.
+/// (Previously they were inserted at the front of a [Vec], which was fast at ~120 frames but `O(n)` per frame).
+/// The plots need a single contiguous slice, so only the frames being displayed are copied into the scratch buffers each frame
 #[derive(Debug, Clone)]
 pub(in crate::ui) struct FrameInfo {
     /// ΔT values, in milliseconds
     ///
     /// # See Also
     /// * [delta_time](imgui::Io::delta_time) - Where this value is obtained from
-    pub deltas: Vec<f32>,
+    pub deltas: RingBuffer<f32>,
     /// Frames per second
     ///
-    /// Inverse of [deltas](FrameTimes::deltas)
-    pub fps: Vec<f32>,
+    /// Inverse of [deltas](FrameInfo::deltas)
+    pub fps: RingBuffer<f32>,
+    /// How many frames have been pushed into [deltas](FrameInfo::deltas), so the [phase timings](FrameInfo::phase_timings) can be matched up with the frame they were recorded on
+    pub frame_number: u64,
+    /// Reused buffers that the displayed (most recent) values are copied into, since plotting needs a contiguous slice
+    pub scratch_deltas: Vec<f32>,
+    pub scratch_fps: Vec<f32>,
+    pub scratch_phase_timings: Vec<FramePhaseTimings>,
+    /// Reused buffer for sorting the displayed deltas when calculating the percentiles
+    pub scratch_sorted_deltas: Vec<f32>,
 
     // Moving averages for displaying the above vecs
     pub smooth_delta_min: f32,
     pub smooth_delta_max: f32,
     pub smooth_fps_min: f32,
     pub smooth_fps_max: f32,
+
+    /// How long each phase of the previous frames took, most recent first
+    ///
+    /// Only recorded while they're being displayed, see [phase_timings_visible](FrameInfo::phase_timings_visible)
+    pub phase_timings: RingBuffer<FramePhaseTimings>,
+    /// Whether the phase timings were displayed last frame. Set to `true` every frame they're displayed, and reset to `false` every frame when they are recorded
+    pub phase_timings_visible: bool,
+}
+
+/// How long (in milliseconds) each phase of rendering a single frame took
+///
+/// Each phase corresponds to a span in the UI thread with the same name
+#[derive(Debug, Copy, Clone, Default)]
+pub(in crate::ui) struct FramePhaseTimings {
+    /// The [frame_number](FrameInfo::frame_number) these timings were recorded on. Set by [record_phase_timings](FrameInfo::record_phase_timings)
+    pub frame: u64,
+    /// Waiting to lock the program data
+    pub obtain_data: f32,
+    /// Rebuilding the font atlas (if needed)
+    pub maybe_rebuild_font: f32,
+    /// Building the UI (our code)
+    pub outer_build_ui: f32,
+    /// Drawing the UI to the screen and swapping buffers
+    pub draw_frame: f32,
+}
+
+impl FramePhaseTimings {
+    pub const PHASE_NAMES: [&'static str; 4] = ["obtain data", "rebuild font", "build ui", "draw frame"];
+
+    /// Returns the phase timings as an array, in the same order as [PHASE_NAMES](FramePhaseTimings::PHASE_NAMES)
+    pub fn as_array(&self) -> [f32; 4] {
+        [self.obtain_data, self.maybe_rebuild_font, self.outer_build_ui, self.draw_frame]
+    }
+}
+
+/// Statistics about a set of frame times (all in milliseconds, apart from the FPS)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(in crate::ui) struct FrameStatistics {
+    pub mean: f32,
+    pub median: f32,
+    pub percentile_95: f32,
+    pub percentile_99: f32,
+    /// The average FPS of the slowest 1% of frames
+    pub one_percent_low_fps: f32,
+}
+
+impl FrameStatistics {
+    /// Calculates the statistics for the frame times in `deltas`, or [None] if there aren't any
+    ///
+    /// The deltas are sorted in `scratch`, so we don't allocate a new buffer every frame
+    pub fn calculate(deltas: &[f32], scratch: &mut Vec<f32>) -> Option<Self> {
+        scratch.clear();
+        scratch.extend_from_slice(deltas);
+        scratch.sort_unstable_by(f32::total_cmp);
+        let sorted = &scratch[..];
+
+        let mean = sorted.iter().sum::<f32>() / sorted.len() as f32;
+        // Always use at least one frame, or we'd have nothing to average when tracking <100 frames
+        let slowest_count = (sorted.len() / 100).max(1);
+        let slowest_mean = sorted[sorted.len().saturating_sub(slowest_count)..].iter().sum::<f32>() / slowest_count as f32;
+        Some(Self {
+            mean,
+            median: percentile(sorted, 0.5)?,
+            percentile_95: percentile(sorted, 0.95)?,
+            percentile_99: percentile(sorted, 0.99)?,
+            one_percent_low_fps: 1000.0 / slowest_mean,
+        })
+    }
+}
+
+/// The value `fraction` (0 to 1) of the way through the `sorted` values (using the nearest one, rather than interpolating), or [None] if there aren't any values
+pub(in crate::ui) fn percentile(sorted: &[f32], fraction: f32) -> Option<f32> {
+    let last_index = sorted.len().checked_sub(1)?;
+    Some(sorted[(last_index as f32 * fraction).round() as usize])
 }
 
 impl FrameInfo {
     pub fn new() -> Self {
+        let num_frames_to_track = read_config_value(|config| config.runtime.ui.frame_info.num_frames_to_track);
         Self {
-            deltas: vec![],
+            deltas: RingBuffer::new(num_frames_to_track),
             smooth_delta_min: 0.0,
             smooth_delta_max: 0.0,
             smooth_fps_min: 0.0,
             smooth_fps_max: 0.0,
-            fps: vec![],
+            fps: RingBuffer::new(num_frames_to_track),
+            frame_number: 0,
+            scratch_deltas: vec![],
+            scratch_fps: vec![],
+            scratch_phase_timings: vec![],
+            scratch_sorted_deltas: vec![],
+            phase_timings: RingBuffer::new(num_frames_to_track),
+            phase_timings_visible: false,
+        }
+    }
+
+    /// Records how long each phase of the current frame took, dropping the oldest timings if we're tracking too many
+    pub fn record_phase_timings(&mut self, timings: FramePhaseTimings) {
+        self.phase_timings.set_capacity(read_config_value(|config| config.runtime.ui.frame_info.num_frames_to_track));
+        self.phase_timings.push(FramePhaseTimings { frame: self.frame_number, ..timings });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn percentile_of_nothing() {
+        assert_eq!(percentile(&[], 0.5), None);
+    }
+
+    #[test]
+    fn percentile_of_one_value() {
+        for fraction in [0.0, 0.5, 0.95, 0.99, 1.0] {
+            assert_eq!(percentile(&[16.0], fraction), Some(16.0));
         }
     }
+
+    #[test]
+    fn percentile_of_ten_values() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(percentile(&sorted, 0.0), Some(1.0));
+        // Index 9 * 0.5 = 4.5, which rounds up to 5
+        assert_eq!(percentile(&sorted, 0.5), Some(6.0));
+        // Index 9 * 0.95 = 8.55, which rounds up to 9
+        assert_eq!(percentile(&sorted, 0.95), Some(10.0));
+        assert_eq!(percentile(&sorted, 1.0), Some(10.0));
+    }
+
+    #[test]
+    fn percentile_of_hundred_and_one_values() {
+        let sorted: Vec<f32> = (0..=100).map(|value| value as f32).collect();
+        assert_eq!(percentile(&sorted, 0.5), Some(50.0));
+        assert_eq!(percentile(&sorted, 0.95), Some(95.0));
+        assert_eq!(percentile(&sorted, 0.99), Some(99.0));
+    }
+
+    #[test]
+    fn statistics_of_nothing() {
+        assert_eq!(FrameStatistics::calculate(&[], &mut vec![]), None);
+    }
+
+    #[test]
+    fn statistics_of_one_frame() {
+        let expected = FrameStatistics {
+            mean: 16.0,
+            median: 16.0,
+            percentile_95: 16.0,
+            percentile_99: 16.0,
+            one_percent_low_fps: 62.5,
+        };
+        assert_eq!(FrameStatistics::calculate(&[16.0], &mut vec![]), Some(expected));
+    }
+
+    #[test]
+    fn statistics_sorts_the_deltas() {
+        // Scratch starts with junk in it, to make sure it's cleared first
+        let mut scratch = vec![1000.0; 3];
+        let expected = FrameStatistics {
+            mean: 2.5,
+            // Index 3 * 0.5 = 1.5, which rounds up to 2
+            median: 3.0,
+            percentile_95: 4.0,
+            percentile_99: 4.0,
+            // Fewer than 100 frames, so it's just the slowest one
+            one_percent_low_fps: 250.0,
+        };
+        assert_eq!(FrameStatistics::calculate(&[4.0, 1.0, 3.0, 2.0], &mut scratch), Some(expected));
+    }
 }
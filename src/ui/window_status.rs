@@ -0,0 +1,106 @@
+//! Shows what the engine is doing outside of the app: in the OS window's title, and (on Windows) as a progress bar on the taskbar button
+
+use crate::build::PROJECT_NAME;
+use crate::engine::{round_to_millis, EngineStatus};
+use crate::helper::logging::event_targets::*;
+use crate::ui::ui_system::UiManagers;
+use imgui_winit_support::winit::window::Window;
+use tracing::{debug, trace};
+#[cfg(windows)]
+use {
+    crate::helper::logging::format_report_display,
+    color_eyre::{eyre::WrapErr, Help},
+    imgui_winit_support::winit::platform::windows::WindowExtWindows,
+    tracing::warn,
+    windows::Win32::Foundation::HWND,
+    windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED},
+    windows::Win32::UI::Shell::{ITaskbarList3, TaskbarList, TBPF_NOPROGRESS, TBPF_NORMAL, TBPF_PAUSED},
+};
+
+/// The title the OS window should have while the engine has the given status
+fn window_title(status: EngineStatus) -> String {
+    let status = match status {
+        EngineStatus::Idle => "Idle".to_string(),
+        EngineStatus::Rendering { completed, total } => {
+            let percent = if total == 0 { 0.0 } else { completed as f32 / total as f32 * 100.0 };
+            format!("Rendering pass {completed}/{total} ({percent:.0}%)")
+        }
+        EngineStatus::Finished { took } => format!("Render complete (took {})", humantime::format_duration(round_to_millis(took))),
+        EngineStatus::Paused => "Paused".to_string(),
+    };
+    format!("{PROJECT_NAME} — {status}")
+}
+
+/// Updates the window title and taskbar progress to match the engine's status.
+///
+/// Called every frame, but only touches the window when the title actually changes, since setting it is a (comparatively slow) call into the OS
+pub(in crate::ui) fn update_window_status(window: &Window, managers: &mut UiManagers, status: EngineStatus) {
+    let title = window_title(status);
+    if title == managers.window_title {
+        trace!(target: UI_TRACE_RENDER, "window title unchanged");
+        return;
+    }
+
+    debug!(target: UI_DEBUG_GENERAL, title, "updating window title");
+    window.set_title(&title);
+    managers.taskbar_progress.update(window, status);
+    managers.window_title = title;
+}
+
+/// The progress bar shown on the app's taskbar button. Does nothing on platforms other than Windows
+#[derive(Debug, Clone)]
+pub(in crate::ui) struct TaskbarProgress {
+    /// [None] if the taskbar couldn't be accessed, in which case there's nothing we can do
+    #[cfg(windows)]
+    taskbar: Option<ITaskbarList3>,
+}
+
+#[cfg(windows)]
+impl TaskbarProgress {
+    pub fn new() -> Self {
+        let result = unsafe {
+            // winit has usually already initialised COM on this thread, in which case this fails harmlessly, so the result can be ignored
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            CoCreateInstance::<_, ITaskbarList3>(&TaskbarList, None, CLSCTX_INPROC_SERVER).and_then(|taskbar| taskbar.HrInit().map(|()| taskbar))
+        };
+        let taskbar = match result.wrap_err("could not access the taskbar").note("render progress won't be shown on the taskbar") {
+            Ok(taskbar) => Some(taskbar),
+            Err(report) => {
+                warn!(target: GENERAL_WARNING_NON_FATAL, report = format_report_display(&report));
+                None
+            }
+        };
+        Self { taskbar }
+    }
+
+    fn update(&self, window: &Window, status: EngineStatus) {
+        let taskbar = match &self.taskbar {
+            Some(taskbar) => taskbar,
+            None => return,
+        };
+        let hwnd = HWND(window.hwnd());
+        let result = unsafe {
+            match status {
+                EngineStatus::Rendering { completed, total } => taskbar
+                    .SetProgressState(hwnd, TBPF_NORMAL)
+                    .and_then(|()| taskbar.SetProgressValue(hwnd, completed as u64, total as u64)),
+                // Keeps showing how far the render had got, just in the paused colour
+                EngineStatus::Paused => taskbar.SetProgressState(hwnd, TBPF_PAUSED),
+                EngineStatus::Idle | EngineStatus::Finished { .. } => taskbar.SetProgressState(hwnd, TBPF_NOPROGRESS),
+            }
+        };
+        if let Err(error) = result {
+            // Not worth bothering the user over, the title still shows the progress
+            warn!(target: GENERAL_WARNING_NON_FATAL, ?error, "could not update taskbar progress");
+        }
+    }
+}
+
+#[cfg(not(windows))]
+impl TaskbarProgress {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn update(&self, _window: &Window, _status: EngineStatus) {}
+}
@@ -0,0 +1,116 @@
+//! Checks that the app shuts down properly when the OS asks it to (see `program::register_shutdown_handler`)
+//!
+//! These run the real binary, so they need a display for the window to open on
+#![cfg(unix)]
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the app to start up, or to exit, before giving up
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+fn has_display() -> bool {
+    ["DISPLAY", "WAYLAND_DISPLAY"].into_iter().any(|var| std::env::var_os(var).is_some())
+}
+
+fn app_temp_dir(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rust_ray_{test_name}_{}", std::process::id()))
+}
+
+/// Copies the app into its own temporary folder, so that the config it saves as it exits doesn't overwrite the real one
+///
+/// The config is saved next to the executable, which is why the whole binary has to be copied and not just run in a different working directory
+fn copy_app_to_temp_dir(test_name: &str) -> PathBuf {
+    let dir = app_temp_dir(test_name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("could not create temp dir");
+    let app_path = dir.join("rust_ray");
+    fs::copy(env!("CARGO_BIN_EXE_rust_ray"), &app_path).expect("could not copy app to temp dir");
+    app_path
+}
+
+/// Starts a copy of the app (see [copy_app_to_temp_dir]), and waits until it has registered its signal handler (otherwise the signal would just kill it)
+fn start_app(test_name: &str) -> Child {
+    let app_path = copy_app_to_temp_dir(test_name);
+    let mut app = Command::new(&app_path)
+        .current_dir(app_path.parent().expect("app should be in the temp dir"))
+        // The resources aren't next to the copy, so point it at the ones in the source tree
+        .env("RUST_RAY_RESOURCES", concat!(env!("CARGO_MANIFEST_DIR"), "/src/resources/app_resources"))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("could not start app");
+
+    let stdout = app.stdout.take().expect("stdout should be piped");
+    let (started_sender, started_receiver) = mpsc::channel();
+    // Keep reading the logs even once it's started, so the app doesn't block on a full pipe
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            // Logged just after the handler is registered
+            if line.contains("starting program") {
+                let _ = started_sender.send(());
+            }
+        }
+    });
+    if started_receiver.recv_timeout(TIMEOUT).is_err() {
+        let _ = app.kill();
+        panic!("app didn't start within {TIMEOUT:?}");
+    }
+    app
+}
+
+fn send_sigint(app: &Child) {
+    let status = Command::new("kill").args(["-INT", &app.id().to_string()]).status().expect("could not run `kill`");
+    assert!(status.success(), "`kill` failed: {status}");
+}
+
+fn wait_for_exit(app: &mut Child) -> ExitStatus {
+    let start = Instant::now();
+    loop {
+        if let Some(status) = app.try_wait().expect("could not check if app exited") {
+            return status;
+        }
+        if start.elapsed() > TIMEOUT {
+            let _ = app.kill();
+            panic!("app didn't exit within {TIMEOUT:?}");
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn sigint_exits_cleanly() {
+    if !has_display() {
+        eprintln!("skipping, there's no display for the app's window");
+        return;
+    }
+    let mut app = start_app("sigint_exits_cleanly");
+    send_sigint(&app);
+    let status = wait_for_exit(&mut app);
+    assert!(status.success(), "app exited with {status}");
+    // Auto-save is on by default, so a clean exit should have saved the config next to the copy
+    let config_path = app_temp_dir("sigint_exits_cleanly").join("config.ron");
+    assert!(config_path.is_file(), "config wasn't saved to {config_path:?}");
+    let _ = fs::remove_dir_all(app_temp_dir("sigint_exits_cleanly"));
+}
+
+#[test]
+fn second_sigint_exits_immediately() {
+    if !has_display() {
+        eprintln!("skipping, there's no display for the app's window");
+        return;
+    }
+    let mut app = start_app("second_sigint_exits_immediately");
+    send_sigint(&app);
+    // Give the handler a moment to see the first one, otherwise the OS might merge the two signals together
+    thread::sleep(Duration::from_millis(50));
+    send_sigint(&app);
+    let status = wait_for_exit(&mut app);
+    assert_eq!(status.code(), Some(130), "app exited with {status}");
+    let _ = fs::remove_dir_all(app_temp_dir("second_sigint_exits_immediately"));
+}